@@ -0,0 +1,11 @@
+#![no_main]
+
+use advent_of_code::{q1::Q1, solution::Solution};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = Q1::parse(input) {
+        Q1::part1(&parsed);
+        Q1::part2(&parsed);
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use advent_of_code::{q10::Q10, solution::Solution};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = Q10::parse(input) {
+        Q10::part1(&parsed);
+        Q10::part2(&parsed);
+    }
+});
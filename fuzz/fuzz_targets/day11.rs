@@ -0,0 +1,11 @@
+#![no_main]
+
+use advent_of_code::{q11::Q11, solution::Solution};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = Q11::parse(input) {
+        Q11::part1(&parsed);
+        Q11::part2(&parsed);
+    }
+});
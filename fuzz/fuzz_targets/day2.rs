@@ -0,0 +1,11 @@
+#![no_main]
+
+use advent_of_code::{q2::Q2, solution::Solution};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = Q2::parse(input) {
+        Q2::part1(&parsed);
+        Q2::part2(&parsed);
+    }
+});
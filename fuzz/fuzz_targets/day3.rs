@@ -0,0 +1,11 @@
+#![no_main]
+
+use advent_of_code::{q3::Q3, solution::Solution};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = Q3::parse(input) {
+        Q3::part1(&parsed);
+        Q3::part2(&parsed);
+    }
+});
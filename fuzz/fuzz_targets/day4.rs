@@ -0,0 +1,11 @@
+#![no_main]
+
+use advent_of_code::{q4::Q4, solution::Solution};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = Q4::parse(input) {
+        Q4::part1(&parsed);
+        Q4::part2(&parsed);
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use advent_of_code::{q5::Q5, solution::Solution};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = Q5::parse(input) {
+        Q5::part1(&parsed);
+        Q5::part2(&parsed);
+    }
+});
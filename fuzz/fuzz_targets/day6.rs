@@ -0,0 +1,11 @@
+#![no_main]
+
+use advent_of_code::{q6::Q6, solution::Solution};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = Q6::parse(input) {
+        Q6::part1(&parsed);
+        Q6::part2(&parsed);
+    }
+});
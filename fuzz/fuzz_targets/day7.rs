@@ -0,0 +1,11 @@
+#![no_main]
+
+use advent_of_code::{q7::Q7, solution::Solution};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = Q7::parse(input) {
+        Q7::part1(&parsed);
+        Q7::part2(&parsed);
+    }
+});
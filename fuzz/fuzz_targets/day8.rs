@@ -0,0 +1,11 @@
+#![no_main]
+
+use advent_of_code::{q8::Q8, solution::Solution};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = Q8::parse(input) {
+        Q8::part1(&parsed);
+        Q8::part2(&parsed);
+    }
+});
@@ -0,0 +1,17 @@
+#![no_main]
+
+use advent_of_code::{q9::{count_tail_positions_in, Q9}, solution::Solution};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    // Long moves are valid, just too slow to simulate while fuzzing
+    if input.split_whitespace().filter_map(|word| word.parse::<i64>().ok()).any(|steps| steps > 10_000) {
+        return;
+    }
+    if let Ok(parsed) = Q9::parse(input) {
+        Q9::part1(&parsed);
+        Q9::part2(&parsed);
+    }
+    // The streaming parser reads the same format a line at a time
+    let _ = count_tail_positions_in(input.as_bytes(), 10);
+});
@@ -0,0 +1,280 @@
+use std::{io::{self, IsTerminal, Write}, time::Duration};
+
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+use crate::error::{Error, Result};
+
+/**
+ * The fastest and slowest a simulation can play
+ */
+const MIN_DELAY: Duration = Duration::from_millis(10);
+const MAX_DELAY: Duration = Duration::from_secs(5);
+
+const HELP: &str = "space: pause  left/right: step  +/-: speed  home/end: jump  q: quit";
+
+/**
+ * One step of a simulation drawn as text, with a note on what just happened
+ * for the status line
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    pub picture: String,
+    pub status: String,
+}
+
+impl Frame {
+    pub fn new(picture: impl Into<String>, status: impl Into<String>) -> Self {
+        Self { picture: picture.into(), status: status.into() }
+    }
+}
+
+/**
+ * What the viewer can ask the player to do
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Control {
+    PlayPause,
+    StepForward,
+    StepBack,
+    Faster,
+    Slower,
+    First,
+    Last,
+    Quit,
+}
+
+/**
+ * The key bindings for each control
+ */
+pub fn control_for(key: KeyEvent) -> Option<Control> {
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Some(Control::Quit),
+        KeyCode::Char(' ') | KeyCode::Char('p') => Some(Control::PlayPause),
+        KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('.') => Some(Control::StepForward),
+        KeyCode::Left | KeyCode::Char('h') | KeyCode::Char(',') => Some(Control::StepBack),
+        KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Up => Some(Control::Faster),
+        KeyCode::Char('-') | KeyCode::Down => Some(Control::Slower),
+        KeyCode::Home | KeyCode::Char('g') => Some(Control::First),
+        KeyCode::End | KeyCode::Char('G') => Some(Control::Last),
+        KeyCode::Char('q') | KeyCode::Esc => Some(Control::Quit),
+        _ => None,
+    }
+}
+
+/**
+ * Which frame is showing, whether it's moving on by itself and how quickly.
+ * Kept apart from the terminal so it can be driven by anything
+ */
+#[derive(Debug)]
+pub struct Player {
+    frames: Vec<Frame>,
+    current: usize,
+    paused: bool,
+    delay: Duration,
+}
+
+impl Player {
+    pub fn new(frames: Vec<Frame>, delay: Duration) -> Self {
+        Self { frames, current: 0, paused: false, delay: delay.clamp(MIN_DELAY, MAX_DELAY) }
+    }
+
+    pub fn paused(mut self, paused: bool) -> Self {
+        self.paused = paused;
+        self
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn frame(&self) -> Option<&Frame> {
+        self.frames.get(self.current)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    fn last(&self) -> usize {
+        self.frames.len().saturating_sub(1)
+    }
+
+    /**
+     * Act on a control, returning false once the viewer has quit. Stepping
+     * pauses playback so the frame stays put
+     */
+    pub fn control(&mut self, control: Control) -> bool {
+        match control {
+            Control::PlayPause => {
+                // Playing from the end starts again
+                if self.paused && self.current == self.last() {
+                    self.current = 0;
+                }
+                self.paused = !self.paused;
+            },
+            Control::StepForward => {
+                self.paused = true;
+                self.current = (self.current + 1).min(self.last());
+            },
+            Control::StepBack => {
+                self.paused = true;
+                self.current = self.current.saturating_sub(1);
+            },
+            Control::Faster => self.delay = (self.delay / 2).max(MIN_DELAY),
+            Control::Slower => self.delay = (self.delay * 2).min(MAX_DELAY),
+            Control::First => self.current = 0,
+            Control::Last => self.current = self.last(),
+            Control::Quit => return false,
+        }
+        true
+    }
+
+    /**
+     * Move on a frame if playing, pausing on the last one
+     */
+    pub fn tick(&mut self) {
+        if self.paused {
+            return;
+        }
+        if self.current < self.last() {
+            self.current += 1;
+        }
+        if self.current == self.last() {
+            self.paused = true;
+        }
+    }
+
+    pub fn status_line(&self) -> String {
+        let state = if self.paused { "paused" } else { "playing" };
+        let status = self.frame().map_or("", |frame| frame.status.as_str());
+        format!("step {}/{}  {}  {:?}  | {}", self.current + 1, self.frames.len(), state, self.delay, status)
+    }
+}
+
+fn terminal_error(why: io::Error) -> Error {
+    Error::io("<terminal>", why)
+}
+
+fn draw(out: &mut impl Write, player: &Player) -> io::Result<()> {
+    queue!(out, Clear(ClearType::All), MoveTo(0, 0))?;
+    if let Some(frame) = player.frame() {
+        // Raw mode doesn't return to the start of the line by itself
+        for line in frame.picture.lines() {
+            write!(out, "{}\r\n", line)?;
+        }
+    }
+    write!(out, "\r\n{}\r\n{}", player.status_line(), HELP)?;
+    out.flush()
+}
+
+fn play_in_terminal(out: &mut impl Write, player: &mut Player) -> io::Result<()> {
+    loop {
+        draw(out, player)?;
+        if !player.is_paused() && !event::poll(player.delay())? {
+            player.tick();
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && control_for(key).is_some_and(|control| !player.control(control)) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/**
+ * Play `frames` in the terminal until the viewer quits. When the output isn't
+ * a terminal every frame is printed in turn instead
+ */
+pub fn play(frames: Vec<Frame>, delay: Duration, start_paused: bool) -> Result<()> {
+    let mut out = io::stdout();
+    if !out.is_terminal() {
+        for (index, frame) in frames.iter().enumerate() {
+            match writeln!(out, "step {}/{}: {}\n{}", index + 1, frames.len(), frame.status, frame.picture) {
+                // Whatever was reading, like `head`, has seen enough
+                Err(why) if why.kind() == io::ErrorKind::BrokenPipe => break,
+                result => result.map_err(terminal_error)?,
+            }
+        }
+        return Ok(());
+    }
+
+    let mut player = Player::new(frames, delay).paused(start_paused);
+    terminal::enable_raw_mode().map_err(terminal_error)?;
+    let result = execute!(out, EnterAlternateScreen, Hide).and_then(|_| play_in_terminal(&mut out, &mut player));
+    // Always hand the terminal back, even if playing failed
+    let restored = execute!(out, Show, LeaveAlternateScreen).and_then(|_| terminal::disable_raw_mode());
+    result.and(restored).map_err(terminal_error)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use super::{control_for, Control, Frame, Player, MIN_DELAY};
+
+    fn player() -> Player {
+        let frames = (1..=3).map(|step| Frame::new(format!("#{}", step), format!("move {}", step))).collect();
+        Player::new(frames, Duration::from_millis(100))
+    }
+
+    #[test]
+    fn test_playback() {
+        let mut player = player();
+        assert_eq!(player.status_line(), "step 1/3  playing  100ms  | move 1");
+        player.tick();
+        assert_eq!(player.frame().unwrap().picture, "#2");
+        player.tick();
+        assert!(player.is_paused());
+        player.tick();
+        assert_eq!(player.current(), 2);
+
+        // Playing again from the end starts from the beginning
+        player.control(Control::PlayPause);
+        assert_eq!((player.current(), player.is_paused()), (0, false));
+    }
+
+    #[test]
+    fn test_controls() {
+        let mut player = player();
+        player.control(Control::StepForward);
+        assert_eq!((player.current(), player.is_paused()), (1, true));
+        player.tick();
+        assert_eq!(player.current(), 1);
+        player.control(Control::StepBack);
+        player.control(Control::StepBack);
+        assert_eq!(player.current(), 0);
+        player.control(Control::Last);
+        player.control(Control::StepForward);
+        assert_eq!(player.current(), 2);
+
+        player.control(Control::Slower);
+        assert_eq!(player.delay(), Duration::from_millis(200));
+        for _ in 0..10 {
+            player.control(Control::Faster);
+        }
+        assert_eq!(player.delay(), MIN_DELAY);
+        assert!(!player.control(Control::Quit));
+    }
+
+    #[test]
+    fn test_key_bindings() {
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        assert_eq!(control_for(key(KeyCode::Char(' '))), Some(Control::PlayPause));
+        assert_eq!(control_for(key(KeyCode::Right)), Some(Control::StepForward));
+        assert_eq!(control_for(key(KeyCode::Char('q'))), Some(Control::Quit));
+        assert_eq!(control_for(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)), Some(Control::Quit));
+        assert_eq!(control_for(key(KeyCode::Char('x'))), None);
+    }
+}
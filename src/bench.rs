@@ -0,0 +1,119 @@
+use std::{hint::black_box, time::{Duration, Instant}};
+
+use serde::{Serialize, Serializer};
+
+use crate::error::Result;
+use crate::solution::Day;
+
+fn serialize_nanos<S: Serializer>(duration: &Duration, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_nanos() as u64)
+}
+
+/**
+ * Summary of the timings for one phase of a day over every iteration
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Stats {
+    #[serde(rename = "min_ns", serialize_with = "serialize_nanos")]
+    pub min: Duration,
+    #[serde(rename = "median_ns", serialize_with = "serialize_nanos")]
+    pub median: Duration,
+    #[serde(rename = "mean_ns", serialize_with = "serialize_nanos")]
+    pub mean: Duration,
+}
+
+impl Stats {
+    /**
+     * Summarize a non-empty set of samples
+     */
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "can't summarize zero samples");
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2
+        } else {
+            sorted[middle]
+        };
+        let total: Duration = sorted.iter().sum();
+        Self {
+            min: sorted[0],
+            median,
+            mean: total / sorted.len() as u32,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DayBenchmark {
+    pub day: u8,
+    pub iterations: usize,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+/**
+ * Time parsing and each part of a day separately, `iterations` times each.
+ * Every iteration parses the input afresh so the parts never share work
+ */
+pub fn benchmark_day(day: &Day, input: &str, iterations: usize) -> Result<DayBenchmark> {
+    let iterations = iterations.max(1);
+    let mut parse_times = Vec::with_capacity(iterations);
+    let mut part1_times = Vec::with_capacity(iterations);
+    let mut part2_times = Vec::with_capacity(iterations);
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let parsed = black_box(day.parse(black_box(input))?);
+        parse_times.push(start.elapsed());
+
+        let start = Instant::now();
+        black_box(day.part1(&parsed));
+        part1_times.push(start.elapsed());
+
+        let start = Instant::now();
+        black_box(day.part2(&parsed));
+        part2_times.push(start.elapsed());
+    }
+
+    Ok(DayBenchmark {
+        day: day.day,
+        iterations,
+        parse: Stats::from_samples(&parse_times),
+        part1: Stats::from_samples(&part1_times),
+        part2: Stats::from_samples(&part2_times),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::get_day;
+
+    use super::{Stats, benchmark_day};
+
+    #[test]
+    fn test_stats() {
+        let samples = [5, 1, 3, 7].map(Duration::from_millis);
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(4));
+        assert_eq!(stats.mean, Duration::from_millis(4));
+
+        let stats = Stats::from_samples(&samples[..3]);
+        assert_eq!(stats.median, Duration::from_millis(3));
+    }
+
+    #[test]
+    fn test_benchmark_day() {
+        let benchmark = benchmark_day(get_day(6).unwrap(), "mjqjpqmgbljsphdztnvjfqwrcgsmlb", 3).unwrap();
+        assert_eq!(benchmark.day, 6);
+        assert_eq!(benchmark.iterations, 3);
+        assert!(benchmark.parse.min <= benchmark.parse.median);
+
+        assert!(benchmark_day(get_day(4).unwrap(), "1-x,2-3", 3).is_err());
+    }
+}
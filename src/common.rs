@@ -1,30 +1,280 @@
-use std::{path::Path, fs::File, io::Read};
+use std::{path::{Path, PathBuf}, fs::File, io::{BufRead, BufReader, Read}, iter::Peekable, str::FromStr};
+
+use memmap2::Mmap;
+
+use crate::error::{Error, Result};
+
+pub mod cycle;
+
+pub mod grid;
+
+pub mod interval;
+
+pub mod parse;
+
+pub mod point;
+
+pub mod search;
 
 /**
  * Read the file from input into a string
  */
-pub fn read_file(input: &str) -> String {
-    let path = Path::new(input);
-    let mut file = match File::open(&path) {
-        Err(why) => panic!("couldn't open; {}", why),
-        Ok(file) => file,
-    };
+pub fn read_file<P: AsRef<Path>>(input: P) -> Result<String> {
+    let path = input.as_ref();
+    let mut file = File::open(path).map_err(|why| Error::io(path, why))?;
 
     let mut s = String::new();
-    file.read_to_string(&mut s).expect("unable to read file");
+    file.read_to_string(&mut s).map_err(|why| Error::io(path, why))?;
 
-    s
+    Ok(s)
 }
 
 /**
  * Split the file on new lines and remove any lines with 0 length
  */
 pub fn parse_lines(s: &str) -> Vec<&str> {
-    s.split('\n').filter(|s| { s.trim().len() > 0}).collect()
+    s.split('\n').filter(|s| { !s.trim().is_empty()}).collect()
+}
+
+/**
+ * A line of puzzle input that knows where it came from, so parsers can
+ * report exactly what they didn't understand
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct Line<'a> {
+    pub day: u8,
+    /// 1 based line number within the input
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /**
+     * A parse error pointing at `text`, which should be a slice of this line
+     */
+    pub fn error(&self, text: &str, reason: impl Into<String>) -> Error {
+        Error::parse(self.day, self.number, self.text, text, reason)
+    }
+
+    /**
+     * A parse error pointing just past the end of the line, for when something is missing
+     */
+    pub fn error_at_end(&self, reason: impl Into<String>) -> Error {
+        self.error(&self.text[self.text.len()..], reason)
+    }
+
+    /**
+     * Parse a token taken from this line, describing it as `what` if it's invalid
+     */
+    pub fn parse<T: FromStr>(&self, token: &str, what: &str) -> Result<T> {
+        token.parse().map_err(|_| self.error(token, format!("expected {}, found", what)))
+    }
+
+    /**
+     * Get the `index`th space separated token on this line
+     */
+    pub fn token(&self, index: usize, what: &str) -> Result<&'a str> {
+        self.text
+            .split(' ')
+            .nth(index)
+            .ok_or_else(|| self.error_at_end(format!("expected {}", what)))
+    }
+}
+
+/**
+ * Split the input on new lines, skipping blank lines like `parse_lines`, but
+ * remembering each line's number
+ */
+pub fn numbered_lines(day: u8, s: &str) -> impl Iterator<Item = Line<'_>> {
+    s.split('\n')
+        .enumerate()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .map(move |(index, text)| Line { day, number: index + 1, text })
 }
 
+/**
+ * Split the input into blocks of consecutive non-blank lines, the way Q1, Q5
+ * and Q11 separate groups with a blank line. Lazy, so only one block is held at
+ * a time
+ */
+pub fn blocks(day: u8, s: &str) -> Blocks<impl Iterator<Item = Line<'_>>> {
+    Blocks { lines: numbered_lines(day, s).peekable() }
+}
+
+pub struct Blocks<I: Iterator> {
+    lines: Peekable<I>,
+}
+
+impl<'a, I: Iterator<Item = Line<'a>>> Iterator for Blocks<I> {
+    type Item = Vec<Line<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut block = vec![self.lines.next()?];
+        while let Some(line) = self.lines.next_if(|line| line.number == block.last().unwrap().number + 1) {
+            block.push(line);
+        }
+        Some(block)
+    }
+}
+
+/**
+ * A line read from a stream. Unlike `Line` it owns its text, since the reader
+ * reuses its buffer for the next line
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct OwnedLine {
+    pub day: u8,
+    /// 1 based line number within the input
+    pub number: usize,
+    pub text: String,
+}
+
+impl OwnedLine {
+    pub fn as_line(&self) -> Line<'_> {
+        Line { day: self.day, number: self.number, text: &self.text }
+    }
+}
+
+/**
+ * Read lines one at a time from `reader`, skipping blank lines like
+ * `numbered_lines`, without holding the rest of the input in memory
+ */
+pub fn read_lines<R: BufRead>(day: u8, reader: R) -> ReadLines<R> {
+    ReadLines { day, reader, source: PathBuf::from("<input>"), number: 0 }
+}
+
+/**
+ * Stream the lines of a file
+ */
+pub fn open_lines<P: AsRef<Path>>(day: u8, path: P) -> Result<ReadLines<BufReader<File>>> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|why| Error::io(path, why))?;
+    Ok(ReadLines { source: path.to_path_buf(), ..read_lines(day, BufReader::new(file)) })
+}
+
+pub struct ReadLines<R> {
+    day: u8,
+    reader: R,
+    /// Where the lines are coming from, for errors
+    source: PathBuf,
+    number: usize,
+}
+
+impl<R: BufRead> ReadLines<R> {
+    /**
+     * Group the remaining lines into blocks separated by blank lines, like `blocks`
+     */
+    pub fn blocks(self) -> ReadBlocks<R> {
+        ReadBlocks { lines: self.peekable() }
+    }
+}
+
+impl<R: BufRead> Iterator for ReadLines<R> {
+    type Item = Result<OwnedLine>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut text = String::new();
+        loop {
+            text.clear();
+            match self.reader.read_line(&mut text) {
+                Ok(0) => return None,
+                Ok(_) => self.number += 1,
+                Err(why) => return Some(Err(Error::io(&self.source, why))),
+            }
+            let trimmed = text.trim_end_matches(['\n', '\r']);
+            if !trimmed.trim().is_empty() {
+                text.truncate(trimmed.len());
+                return Some(Ok(OwnedLine { day: self.day, number: self.number, text }));
+            }
+        }
+    }
+}
+
+pub struct ReadBlocks<R: BufRead> {
+    lines: Peekable<ReadLines<R>>,
+}
+
+impl<R: BufRead> Iterator for ReadBlocks<R> {
+    type Item = Result<Vec<OwnedLine>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut block = match self.lines.next()? {
+            Ok(line) => vec![line],
+            Err(why) => return Some(Err(why)),
+        };
+        loop {
+            let next_number = block.last().unwrap().number + 1;
+            match self.lines.next_if(|line| matches!(line, Ok(line) if line.number == next_number)) {
+                Some(line) => block.push(line.unwrap()),
+                None => return Some(Ok(block)),
+            }
+        }
+    }
+}
+
+/**
+ * A file mapped into memory, so huge inputs can be scanned without being read
+ * into a `String` first
+ */
+pub struct MappedInput {
+    path: PathBuf,
+    /// Empty files can't be mapped
+    mmap: Option<Mmap>,
+}
+
+impl MappedInput {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|why| Error::io(path, why))?;
+        let len = file.metadata().map_err(|why| Error::io(path, why))?.len();
+        // Safety: the input files are never modified while a day is being solved
+        let mmap = if len == 0 {
+            None
+        } else {
+            Some(unsafe { Mmap::map(&file) }.map_err(|why| Error::io(path, why))?)
+        };
+        Ok(Self { path: path.to_path_buf(), mmap })
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        self.mmap.as_deref().unwrap_or_default()
+    }
+
+    /**
+     * The file as text, failing if it isn't UTF-8
+     */
+    pub fn as_str(&self) -> Result<&str> {
+        std::str::from_utf8(self.bytes())
+            .map_err(|why| Error::io(&self.path, std::io::Error::new(std::io::ErrorKind::InvalidData, why)))
+    }
+
+    /**
+     * Numbered lines of the mapped text, skipping blank lines
+     */
+    pub fn lines(&self, day: u8) -> Result<impl Iterator<Item = Line<'_>>> {
+        Ok(numbered_lines(day, self.as_str()?))
+    }
+}
+
+#[cfg(test)]
 mod test {
-    use crate::common::parse_lines;
+    use std::{env, fs, io::Cursor};
+
+    use crate::common::{parse_lines, numbered_lines, blocks, read_lines, MappedInput};
+
+    #[test]
+    fn test_numbered_lines() {
+        let input = "1-2
+
+ab
+";
+        let lines: Vec<(usize, &str)> = numbered_lines(4, input).map(|line| (line.number, line.text)).collect();
+        assert_eq!(lines, vec![(1, "1-2"), (3, "ab")]);
+
+        let line = numbered_lines(4, input).nth(1).unwrap();
+        assert!(line.parse::<u32>(line.text, "a number").is_err());
+        assert!(line.token(1, "a second token").is_err());
+    }
 
     #[test]
     fn test_parse_lines() {
@@ -47,4 +297,49 @@ CrZsJsPPZsGzwwsLwLmpwMDw
 
         assert_eq!(parse_lines(input), expected_output);
     }
+
+    #[test]
+    fn test_blocks() {
+        let input = "1\n2\n\n\n3\n\n4\n5\n";
+        let numbers: Vec<Vec<usize>> = blocks(1, input)
+            .map(|block| block.iter().map(|line| line.number).collect())
+            .collect();
+        assert_eq!(numbers, vec![vec![1, 2], vec![5], vec![7, 8]]);
+
+        let streamed: Vec<Vec<usize>> = read_lines(1, Cursor::new(input)).blocks()
+            .map(|block| block.unwrap().iter().map(|line| line.number).collect())
+            .collect();
+        assert_eq!(streamed, numbers);
+    }
+
+    #[test]
+    fn test_read_lines() {
+        let lines: Vec<(usize, String)> = read_lines(4, Cursor::new("1-2\r\n\n  \nab"))
+            .map(|line| line.map(|line| (line.number, line.text)))
+            .collect::<crate::Result<_>>()
+            .unwrap();
+        assert_eq!(lines, vec![(1, "1-2".to_string()), (4, "ab".to_string())]);
+
+        let line = read_lines(4, Cursor::new("\nx")).next().unwrap().unwrap();
+        let error = line.as_line().parse::<u32>(&line.text, "a number").unwrap_err();
+        assert!(error.to_string().starts_with("day 4 input, line 2, column 1: expected a number"), "{}", error);
+    }
+
+    #[test]
+    fn test_mapped_input() {
+        let dir = env::temp_dir().join(format!("advent-of-code-mmap-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("text"), "R 4\n\nU 4\n").unwrap();
+        fs::write(dir.join("empty"), "").unwrap();
+        fs::write(dir.join("binary"), [0xff, 0xfe]).unwrap();
+
+        let mapped = MappedInput::open(dir.join("text")).unwrap();
+        assert_eq!(mapped.bytes(), b"R 4\n\nU 4\n");
+        let lines: Vec<usize> = mapped.lines(9).unwrap().map(|line| line.number).collect();
+        assert_eq!(lines, vec![1, 3]);
+        assert_eq!(MappedInput::open(dir.join("empty")).unwrap().as_str().unwrap(), "");
+        assert!(MappedInput::open(dir.join("binary")).unwrap().as_str().is_err());
+        assert!(MappedInput::open(dir.join("missing")).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
 }
@@ -0,0 +1,229 @@
+use std::{collections::HashMap, hash::Hash};
+
+/**
+ * Where a sequence of states starts repeating. The state after `start` steps
+ * is the first to come round again, `length` steps later
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: u64,
+    pub length: u64,
+}
+
+impl Cycle {
+    /**
+     * The earliest step with the same state as step `n`
+     */
+    pub fn index_at(&self, n: u64) -> u64 {
+        if n < self.start + self.length {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/**
+ * Floyd's tortoise and hare. Needs no memory beyond three states, but steps
+ * through the sequence several times
+ */
+pub fn floyd<S: Clone + PartialEq>(initial: S, step: impl Fn(&S) -> S) -> Cycle {
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&step(&hare));
+    }
+
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+    Cycle { start, length }
+}
+
+/**
+ * Brent's algorithm. Like `floyd` it only keeps a couple of states, but usually
+ * calls `step` fewer times
+ */
+pub fn brent<S: Clone + PartialEq>(initial: S, step: impl Fn(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, length }
+}
+
+/**
+ * The state after `n` steps, given the sequence's cycle, without taking more
+ * than `cycle.start + cycle.length` steps
+ */
+pub fn state_at<S>(initial: S, step: impl Fn(&S) -> S, cycle: Cycle, n: u64) -> S {
+    (0..cycle.index_at(n)).fold(initial, |state, _| step(&state))
+}
+
+/**
+ * Every state of a sequence up to the first repeat, found by remembering a
+ * hash of each one
+ */
+#[derive(Debug, Clone)]
+pub struct History<S> {
+    states: Vec<S>,
+    cycle: Cycle,
+}
+
+/**
+ * Step from `initial` until a state repeats
+ */
+pub fn find_cycle<S: Hash + Eq + Clone>(initial: S, step: impl FnMut(&S) -> S) -> History<S> {
+    find_cycle_within(initial, u64::MAX, step).expect("a sequence that never repeats")
+}
+
+/**
+ * Like `find_cycle`, but give up if none of the first `limit` states repeat,
+ * for sequences whose cycle could be too long to be worth remembering
+ */
+pub fn find_cycle_within<S: Hash + Eq + Clone>(initial: S, limit: u64, mut step: impl FnMut(&S) -> S) -> Option<History<S>> {
+    let mut seen = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = initial;
+    loop {
+        if states.len() as u64 >= limit {
+            return None;
+        }
+        if let Some(&start) = seen.get(&state) {
+            let cycle = Cycle { start: start as u64, length: (states.len() - start) as u64 };
+            return Some(History { states, cycle });
+        }
+        seen.insert(state.clone(), states.len());
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+impl<S> History<S> {
+    pub fn cycle(&self) -> Cycle {
+        self.cycle
+    }
+
+    /**
+     * The states before the first repeat, starting with the initial state
+     */
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+
+    pub fn state_at(&self, n: u64) -> &S {
+        &self.states[self.cycle.index_at(n) as usize]
+    }
+
+    /**
+     * How many times each of `states()` occurs in the first `n` states,
+     * steps `0..n`
+     */
+    pub fn visits(&self, n: u64) -> Vec<u64> {
+        let Cycle { start, length } = self.cycle;
+        let mut visits = vec![0; self.states.len()];
+        for (index, count) in visits.iter_mut().enumerate() {
+            let index = index as u64;
+            *count = if index >= n {
+                0
+            } else if index < start {
+                1
+            } else {
+                // Visits at index, index + length, index + 2 * length, ... below n
+                (n - index).div_ceil(length)
+            };
+        }
+        visits
+    }
+
+    /**
+     * The sum of `metric` over the first `n` states, steps `0..n`
+     */
+    pub fn total(&self, n: u64, metric: impl Fn(&S) -> u64) -> u64 {
+        self.states.iter()
+            .zip(self.visits(n))
+            .map(|(state, visits)| metric(state) * visits)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Cycle, brent, find_cycle, find_cycle_within, floyd, state_at};
+
+    fn step(x: &u64) -> u64 {
+        (x * x + 1) % 255
+    }
+
+    fn brute_force(n: u64) -> Vec<u64> {
+        std::iter::successors(Some(3), |x| Some(step(x))).take(n as usize).collect()
+    }
+
+    #[test]
+    fn test_detectors_agree() {
+        let history = find_cycle(3, step);
+        let cycle = history.cycle();
+        assert_eq!(floyd(3, step), cycle);
+        assert_eq!(brent(3, step), cycle);
+
+        let states = brute_force(cycle.start + 3 * cycle.length);
+        assert_eq!(states[cycle.start as usize], states[(cycle.start + cycle.length) as usize]);
+        assert_eq!(&states[..history.states().len()], history.states());
+    }
+
+    #[test]
+    fn test_extrapolation() {
+        let history = find_cycle(3, step);
+        let states = brute_force(200);
+        for n in [0, 1, 5, 17, 100, 199] {
+            assert_eq!(*history.state_at(n), states[n as usize]);
+            assert_eq!(state_at(3, step, history.cycle(), n), states[n as usize]);
+            assert_eq!(history.total(n, |&x| x), states[..n as usize].iter().sum::<u64>());
+        }
+
+        // A cycle of 0, 1, 2 after a run up of 10 and 11
+        let history = find_cycle(10u64, |&x| if x >= 10 { x + 1 } else { (x + 1) % 3 } % 12);
+        assert_eq!(history.cycle(), Cycle { start: 2, length: 3 });
+        assert_eq!(*history.state_at(1_000_000_000), (1_000_000_000 - 2) % 3);
+        assert_eq!(history.total(1_000_000_000, |_| 1), 1_000_000_000);
+        assert_eq!(history.visits(8), vec![1, 1, 2, 2, 2]);
+
+        // The repeat comes round at the sixth state
+        assert!(find_cycle_within(10u64, 5, |&x| if x >= 10 { x + 1 } else { (x + 1) % 3 } % 12).is_none());
+        assert!(find_cycle_within(10u64, 6, |&x| if x >= 10 { x + 1 } else { (x + 1) % 3 } % 12).is_some());
+    }
+}
@@ -0,0 +1,230 @@
+use std::{fmt::Display, ops::{Index, IndexMut}};
+
+use crate::common::parse::{self, parse_all};
+use crate::error::Result;
+
+/// A cell of a grid, as (row, column) counting from the top left
+pub type Cell = (usize, usize);
+
+/// A step between cells, as (rows down, columns right)
+pub type Step = (isize, isize);
+
+pub const UP: Step = (-1, 0);
+pub const DOWN: Step = (1, 0);
+pub const LEFT: Step = (0, -1);
+pub const RIGHT: Step = (0, 1);
+
+/// Steps to the cells sharing an edge
+pub const ORTHOGONAL: [Step; 4] = [UP, RIGHT, DOWN, LEFT];
+
+/// Steps to the cells sharing an edge or a corner, clockwise from up
+pub const ALL_DIRECTIONS: [Step; 8] = [UP, (-1, 1), RIGHT, (1, 1), DOWN, (1, -1), LEFT, (-1, -1)];
+
+/**
+ * A rectangular grid of cells stored row by row
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self { width, height, cells: vec![fill; width * height] }
+    }
+
+    /**
+     * Swap rows and columns
+     */
+    pub fn transpose(&self) -> Self {
+        let cells = (0..self.width)
+            .flat_map(|column| self.column(column).cloned().collect::<Vec<T>>())
+            .collect();
+        Self { width: self.height, height: self.width, cells }
+    }
+}
+
+impl<T> Grid<T> {
+    /**
+     * Build a grid from its rows, or `None` if they aren't all the same width
+     */
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        Some(Self { width, height, cells: rows.into_iter().flatten().collect() })
+    }
+
+    /**
+     * Parse a block of single character cells, converting each with `cell`.
+     * `what` describes a valid cell in errors
+     */
+    pub fn parse(day: u8, input: &str, cell: impl Fn(char) -> Option<T>, what: &'static str) -> Result<Self> {
+        let rows = parse_all(day, input, parse::grid(cell, what))?;
+        Ok(Self::from_rows(rows).expect("the grid parser checks the rows are the same width"))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (row, column): Cell) -> bool {
+        row < self.height && column < self.width
+    }
+
+    pub fn get(&self, cell: Cell) -> Option<&T> {
+        self.contains(cell).then(|| &self.cells[cell.0 * self.width + cell.1])
+    }
+
+    pub fn get_mut(&mut self, cell: Cell) -> Option<&mut T> {
+        if !self.contains(cell) {
+            return None;
+        }
+        Some(&mut self.cells[cell.0 * self.width + cell.1])
+    }
+
+    /**
+     * The cell one `step` away from `cell`, if it's inside the grid
+     */
+    pub fn step(&self, (row, column): Cell, (down, right): Step) -> Option<Cell> {
+        let cell = (row.checked_add_signed(down)?, column.checked_add_signed(right)?);
+        self.contains(cell).then_some(cell)
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1)).take(self.height)
+    }
+
+    pub fn column(&self, column: usize) -> impl Iterator<Item = &T> {
+        assert!(column < self.width, "column {} is outside a grid {} wide", column, self.width);
+        self.cells.iter().skip(column).step_by(self.width)
+    }
+
+    /**
+     * Every cell, row by row
+     */
+    pub fn cells(&self) -> impl Iterator<Item = Cell> + '_ {
+        (0..self.height).flat_map(move |row| (0..self.width).map(move |column| (row, column)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Cell, &T)> {
+        self.cells().zip(self.cells.iter())
+    }
+
+    /**
+     * The cells from `start` (not included) to the edge of the grid, one `step` at a time
+     */
+    pub fn ray(&self, start: Cell, step: Step) -> impl Iterator<Item = Cell> + '_ {
+        std::iter::successors(self.step(start, step), move |&cell| self.step(cell, step))
+    }
+
+    /**
+     * The cells one of `steps` away from `cell`, usually `ORTHOGONAL` or `ALL_DIRECTIONS`
+     */
+    pub fn neighbors<'a>(&'a self, cell: Cell, steps: &'a [Step]) -> impl Iterator<Item = Cell> + 'a {
+        steps.iter().filter_map(move |&step| self.step(cell, step))
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+
+    /**
+     * Draw the grid as text, one character per cell
+     */
+    pub fn render(&self, f: impl Fn(&T) -> char) -> String {
+        self.rows()
+            .map(|row| row.iter().map(&f).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+impl<T> Index<Cell> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, cell: Cell) -> &T {
+        let (width, height) = (self.width, self.height);
+        self.get(cell).unwrap_or_else(|| panic!("{:?} is outside a {}x{} grid", cell, width, height))
+    }
+}
+
+impl<T> IndexMut<Cell> for Grid<T> {
+    fn index_mut(&mut self, cell: Cell) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(cell).unwrap_or_else(|| panic!("{:?} is outside a {}x{} grid", cell, width, height))
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, row) in self.rows().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Grid, ALL_DIRECTIONS, ORTHOGONAL, RIGHT, UP};
+
+    fn example() -> Grid<u32> {
+        Grid::parse(8, "123\n456\n", |c| c.to_digit(10), "a digit").unwrap()
+    }
+
+    #[test]
+    fn test_indexing() {
+        let mut grid = example();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 2)], 6);
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.get((0, 3)), None);
+        grid[(0, 0)] = 9;
+        assert_eq!(grid.row(0), &[9, 2, 3]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<u32>>(), vec![2, 5]);
+        assert!(Grid::from_rows(vec![vec![1], vec![2, 3]]).is_none());
+        assert!(Grid::parse(8, "12\n3\n", |c| c.to_digit(10), "a digit").is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "(2, 0) is outside a 3x2 grid")]
+    fn test_index_out_of_bounds() {
+        let _ = example()[(2, 0)];
+    }
+
+    #[test]
+    fn test_rays_and_neighbors() {
+        let grid = example();
+        assert_eq!(grid.ray((1, 0), RIGHT).collect::<Vec<_>>(), vec![(1, 1), (1, 2)]);
+        assert_eq!(grid.ray((0, 0), UP).count(), 0);
+        assert_eq!(grid.neighbors((0, 0), &ORTHOGONAL).collect::<Vec<_>>(), vec![(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbors((1, 1), &ALL_DIRECTIONS).count(), 5);
+    }
+
+    #[test]
+    fn test_transpose_and_render() {
+        let grid = example();
+        let transposed = grid.transpose();
+        assert_eq!(transposed.to_string(), "14\n25\n36");
+        assert_eq!(transposed.transpose(), grid);
+        assert_eq!(grid.render(|&n| if n % 2 == 0 { '#' } else { '.' }), ".#.\n#.#");
+    }
+}
@@ -0,0 +1,322 @@
+use std::fmt::Display;
+
+/**
+ * An inclusive range of integers, never empty. The bounds are private so
+ * `new` is the only way to make one
+ */
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct Interval {
+    lower: i64,
+    upper: i64,
+}
+
+impl Interval {
+    /**
+     * The interval from `lower` to `upper` inclusive, or `None` if it would be empty
+     */
+    pub fn new(lower: i64, upper: i64) -> Option<Self> {
+        (lower <= upper).then_some(Self { lower, upper })
+    }
+
+    pub fn lower(&self) -> i64 {
+        self.lower
+    }
+
+    pub fn upper(&self) -> i64 {
+        self.upper
+    }
+
+    /**
+     * The number of values in the interval. A `u128`, since the whole range
+     * of `i64` holds one more value than a `u64` can count
+     */
+    pub fn len(&self) -> u128 {
+        self.upper.abs_diff(self.lower) as u128 + 1
+    }
+
+    /**
+     * Always false. `new` won't make an interval whose lower bound is above
+     * its upper bound, so every interval holds at least `lower`
+     */
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    pub fn contains_value(&self, value: i64) -> bool {
+        self.lower <= value && value <= self.upper
+    }
+
+    /**
+     * Whether `other` lies entirely inside this interval
+     */
+    pub fn contains(&self, other: &Self) -> bool {
+        self.lower <= other.lower && self.upper >= other.upper
+    }
+
+    /**
+     * Whether the intervals share at least one value. The same whichever way round they're asked
+     */
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.lower <= other.upper && other.lower <= self.upper
+    }
+
+    /**
+     * Whether the intervals overlap or sit right next to each other, so their
+     * union is a single interval
+     */
+    pub fn touches(&self, other: &Self) -> bool {
+        self.lower <= other.upper.saturating_add(1) && other.lower <= self.upper.saturating_add(1)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        Self::new(self.lower.max(other.lower), self.upper.min(other.upper))
+    }
+
+    /**
+     * The union of two intervals, if it's a single interval
+     */
+    pub fn union(&self, other: &Self) -> Option<Self> {
+        self.touches(other).then(|| Self { lower: self.lower.min(other.lower), upper: self.upper.max(other.upper) })
+    }
+
+    /**
+     * The values in this interval but not `other`, which can be split in two
+     */
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        if !self.overlaps(other) {
+            return vec![*self];
+        }
+        let below = other.lower.checked_sub(1).and_then(|upper| Self::new(self.lower, upper));
+        let above = other.upper.checked_add(1).and_then(|lower| Self::new(lower, self.upper));
+        below.into_iter().chain(above).collect()
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.lower, self.upper)
+    }
+}
+
+/**
+ * A set of integers stored as sorted, disjoint intervals. Overlapping and
+ * adjacent intervals are merged as they're inserted, so queries are a binary
+ * search
+ */
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        // Every interval from `start` to `end` touches the new one
+        let start = self.intervals.partition_point(|other| other.upper.saturating_add(1) < interval.lower);
+        let end = self.intervals.partition_point(|other| other.lower <= interval.upper.saturating_add(1));
+        let merged = self.intervals[start..end]
+            .iter()
+            .fold(interval, |merged, other| merged.union(other).unwrap());
+        self.intervals.splice(start..end, [merged]);
+    }
+
+    pub fn remove(&mut self, interval: Interval) {
+        let start = self.intervals.partition_point(|other| other.upper < interval.lower);
+        let end = self.intervals.partition_point(|other| other.lower <= interval.upper);
+        let remaining: Vec<Interval> = self.intervals[start..end]
+            .iter()
+            .flat_map(|other| other.difference(&interval))
+            .collect();
+        self.intervals.splice(start..end, remaining);
+    }
+
+    /**
+     * The interval holding `value`, if any
+     */
+    fn find(&self, value: i64) -> Option<&Interval> {
+        let index = self.intervals.partition_point(|other| other.upper < value);
+        self.intervals.get(index).filter(|other| other.contains_value(value))
+    }
+
+    pub fn contains_value(&self, value: i64) -> bool {
+        self.find(value).is_some()
+    }
+
+    /**
+     * Whether every value of `interval` is in the set
+     */
+    pub fn covers(&self, interval: &Interval) -> bool {
+        self.find(interval.lower).is_some_and(|other| other.contains(interval))
+    }
+
+    /**
+     * The number of values in the set
+     */
+    pub fn len(&self) -> u128 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /**
+     * The disjoint intervals making up the set, in order
+     */
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    /**
+     * The values between `within.lower` and `within.upper` that aren't in the set
+     */
+    pub fn gaps(&self, within: Interval) -> IntervalSet {
+        let mut gaps = IntervalSet::from_iter([within]);
+        for interval in &self.intervals {
+            gaps.remove(*interval);
+        }
+        gaps
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        union.extend(other.intervals.iter().copied());
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intersection = Vec::new();
+        let (mut left, mut right) = (0, 0);
+        while let (Some(a), Some(b)) = (self.intervals.get(left), other.intervals.get(right)) {
+            intersection.extend(a.intersection(b));
+            if a.upper < b.upper { left += 1 } else { right += 1 }
+        }
+        Self { intervals: intersection }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for interval in &other.intervals {
+            difference.remove(*interval);
+        }
+        difference
+    }
+}
+
+impl Extend<Interval> for IntervalSet {
+    fn extend<I: IntoIterator<Item = Interval>>(&mut self, intervals: I) {
+        for interval in intervals {
+            self.insert(interval);
+        }
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(intervals: I) -> Self {
+        let mut set = Self::new();
+        set.extend(intervals);
+        set
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use proptest::prelude::*;
+
+    use super::{Interval, IntervalSet};
+
+    fn interval() -> impl Strategy<Value = Interval> {
+        (-20i64..20, 0i64..10).prop_map(|(lower, len)| Interval::new(lower, lower + len).unwrap())
+    }
+
+    fn values(intervals: &[Interval]) -> BTreeSet<i64> {
+        intervals.iter().flat_map(|interval| interval.lower..=interval.upper).collect()
+    }
+
+    #[test]
+    fn test_interval() {
+        let a = Interval::new(2, 6).unwrap();
+        let b = Interval::new(4, 8).unwrap();
+        assert!(Interval::new(3, 2).is_none());
+        assert_eq!(a.len(), 5);
+        let everything = Interval::new(i64::MIN, i64::MAX).unwrap();
+        assert_eq!(everything.len(), 1 << 64);
+        assert_eq!(IntervalSet::from_iter([everything]).len(), 1 << 64);
+        assert_eq!(a.intersection(&b), Interval::new(4, 6));
+        assert_eq!(a.union(&b), Interval::new(2, 8));
+        assert_eq!(a.union(&Interval::new(7, 9).unwrap()), Interval::new(2, 9));
+        assert_eq!(a.union(&Interval::new(8, 9).unwrap()), None);
+        assert_eq!(a.difference(&Interval::new(3, 4).unwrap()), vec![Interval::new(2, 2).unwrap(), Interval::new(5, 6).unwrap()]);
+
+        // 6-6 sits inside 4-6, which q4 used to only spot one way round
+        let small = Interval::new(6, 6).unwrap();
+        let large = Interval::new(4, 6).unwrap();
+        assert!(small.overlaps(&large) && large.overlaps(&small));
+    }
+
+    #[test]
+    fn test_interval_set() {
+        let set: IntervalSet = [(12, 12), (2, 4), (16, 20), (5, 8), (14, 16)]
+            .into_iter()
+            .map(|(lower, upper)| Interval::new(lower, upper).unwrap())
+            .collect();
+        assert_eq!(set.intervals(), &[Interval::new(2, 8).unwrap(), Interval::new(12, 12).unwrap(), Interval::new(14, 20).unwrap()]);
+        assert_eq!(set.len(), 15);
+        assert!(set.covers(&Interval::new(3, 7).unwrap()));
+        assert!(!set.covers(&Interval::new(8, 12).unwrap()));
+        assert_eq!(set.gaps(Interval::new(0, 20).unwrap()).intervals(), &[
+            Interval::new(0, 1).unwrap(), Interval::new(9, 11).unwrap(), Interval::new(13, 13).unwrap(),
+        ]);
+    }
+
+    proptest! {
+        #[test]
+        fn overlaps_is_symmetric(a in interval(), b in interval()) {
+            prop_assert_eq!(a.overlaps(&b), b.overlaps(&a));
+            prop_assert_eq!(a.overlaps(&b), a.intersection(&b).is_some());
+        }
+
+        #[test]
+        fn interval_operations_match_sets(a in interval(), b in interval()) {
+            let (a_values, b_values) = (values(&[a]), values(&[b]));
+            let intersection: BTreeSet<i64> = a_values.intersection(&b_values).copied().collect();
+            prop_assert_eq!(values(&a.intersection(&b).into_iter().collect::<Vec<_>>()), intersection);
+            let difference: BTreeSet<i64> = a_values.difference(&b_values).copied().collect();
+            prop_assert_eq!(values(&a.difference(&b)), difference);
+            if let Some(union) = a.union(&b) {
+                prop_assert_eq!(values(&[union]), a_values.union(&b_values).copied().collect::<BTreeSet<i64>>());
+            }
+        }
+
+        #[test]
+        fn set_matches_values(inserted in prop::collection::vec(interval(), 0..8), removed in prop::collection::vec(interval(), 0..4), probe in -25i64..35) {
+            let mut set: IntervalSet = inserted.iter().copied().collect();
+            let mut expected = values(&inserted);
+            for interval in &removed {
+                set.remove(*interval);
+                for value in interval.lower..=interval.upper {
+                    expected.remove(&value);
+                }
+            }
+            prop_assert_eq!(values(set.intervals()), expected.clone());
+            prop_assert_eq!(set.len(), expected.len() as u128);
+            prop_assert_eq!(set.contains_value(probe), expected.contains(&probe));
+            // Stored intervals are sorted and never touch
+            prop_assert!(set.intervals().windows(2).all(|pair| pair[0].upper + 1 < pair[1].lower));
+        }
+
+        #[test]
+        fn set_operations_match_values(a in prop::collection::vec(interval(), 0..6), b in prop::collection::vec(interval(), 0..6)) {
+            let (a_set, b_set): (IntervalSet, IntervalSet) = (a.iter().copied().collect(), b.iter().copied().collect());
+            let (a_values, b_values) = (values(&a), values(&b));
+            prop_assert_eq!(values(a_set.union(&b_set).intervals()), a_values.union(&b_values).copied().collect::<BTreeSet<i64>>());
+            prop_assert_eq!(values(a_set.intersection(&b_set).intervals()), a_values.intersection(&b_values).copied().collect::<BTreeSet<i64>>());
+            prop_assert_eq!(values(a_set.difference(&b_set).intervals()), a_values.difference(&b_values).copied().collect::<BTreeSet<i64>>());
+        }
+    }
+}
@@ -0,0 +1,386 @@
+use std::str::FromStr;
+
+use crate::common::Line;
+use crate::error::{Error, Result};
+
+/**
+ * The unparsed rest of some puzzle input. It remembers the whole input it came
+ * from, so errors can point at the exact line and column that failed
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct Input<'a> {
+    day: u8,
+    source: &'a str,
+    /// The line number of the first line of `source`
+    first_line: usize,
+    rest: &'a str,
+}
+
+pub type PResult<'a, T> = std::result::Result<(T, Input<'a>), Error>;
+
+impl<'a> Input<'a> {
+    pub fn new(day: u8, source: &'a str) -> Self {
+        Self { day, source, first_line: 1, rest: source }
+    }
+
+    /**
+     * Parse a single line, keeping its line number for errors
+     */
+    pub fn from_line(line: &Line<'a>) -> Self {
+        Self { day: line.day, source: line.text, first_line: line.number, rest: line.text }
+    }
+
+    pub fn rest(&self) -> &'a str {
+        self.rest
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rest.is_empty()
+    }
+
+    fn advance(self, len: usize) -> Self {
+        Self { rest: &self.rest[len..], ..self }
+    }
+
+    /**
+     * The input consumed between `self` and `later`
+     */
+    fn consumed(&self, later: &Input<'a>) -> &'a str {
+        &self.rest[..self.rest.len() - later.rest.len()]
+    }
+
+    /**
+     * A parse error pointing at the next `len` bytes of input, cut off at the end of the line
+     */
+    pub fn error(&self, len: usize, reason: impl Into<String>) -> Error {
+        let offset = self.source.len() - self.rest.len();
+        let start = self.source[..offset].rfind('\n').map_or(0, |index| index + 1);
+        let end = self.source[offset..].find('\n').map_or(self.source.len(), |index| offset + index);
+        let number = self.first_line + self.source[..offset].matches('\n').count();
+        let text = &self.source[offset..(offset + len).min(end)];
+        Error::parse(self.day, number, &self.source[start..end], text, reason)
+    }
+
+    /**
+     * A parse error showing the token found instead of what was expected
+     */
+    pub fn expected(&self, what: &str) -> Error {
+        let token = self.rest.find(char::is_whitespace).unwrap_or(self.rest.len());
+        if token == 0 {
+            self.error(0, format!("expected {}", what))
+        } else {
+            self.error(token, format!("expected {}, found", what))
+        }
+    }
+}
+
+/**
+ * Something that consumes the start of an `Input` to produce a `T`. Any
+ * function from `Input` to `PResult` is a parser
+ */
+pub trait Parser<'a, T> {
+    fn parse(&self, input: Input<'a>) -> PResult<'a, T>;
+
+    fn map<U>(self, f: impl Fn(T) -> U) -> impl Parser<'a, U> where Self: Sized {
+        move |input: Input<'a>| self.parse(input).map(|(value, rest)| (f(value), rest))
+    }
+
+    /**
+     * Map the result with a check that can fail, pointing the error at
+     * everything this parser consumed
+     */
+    fn try_map<U>(self, f: impl Fn(T) -> std::result::Result<U, String>) -> impl Parser<'a, U> where Self: Sized {
+        move |input: Input<'a>| {
+            let (value, rest) = self.parse(input)?;
+            let value = f(value).map_err(|reason| input.error(input.consumed(&rest).len(), reason))?;
+            Ok((value, rest))
+        }
+    }
+
+    /**
+     * Parse `self` then `next`, keeping both
+     */
+    fn then<U>(self, next: impl Parser<'a, U>) -> impl Parser<'a, (T, U)> where Self: Sized {
+        move |input: Input<'a>| {
+            let (first, rest) = self.parse(input)?;
+            let (second, rest) = next.parse(rest)?;
+            Ok(((first, second), rest))
+        }
+    }
+
+    /**
+     * Parse `self` then `next`, keeping only `self`
+     */
+    fn left<U>(self, next: impl Parser<'a, U>) -> impl Parser<'a, T> where Self: Sized {
+        self.then(next).map(|(first, _)| first)
+    }
+
+    /**
+     * Parse `self` then `next`, keeping only `next`
+     */
+    fn right<U>(self, next: impl Parser<'a, U>) -> impl Parser<'a, U> where Self: Sized {
+        self.then(next).map(|(_, second)| second)
+    }
+}
+
+impl<'a, T, F: Fn(Input<'a>) -> PResult<'a, T>> Parser<'a, T> for F {
+    fn parse(&self, input: Input<'a>) -> PResult<'a, T> {
+        self(input)
+    }
+}
+
+/**
+ * Exactly the text `expected`, like a keyword or a separator
+ */
+pub fn literal<'a>(expected: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: Input<'a>| match input.rest.strip_prefix(expected) {
+        Some(_) => Ok((&input.rest[..expected.len()], input.advance(expected.len()))),
+        None => Err(input.expected(&format!("`{}`", expected.trim()))),
+    }
+}
+
+/**
+ * One or more characters matching `predicate`, described as `what` in errors
+ */
+pub fn take_while1<'a>(predicate: impl Fn(char) -> bool, what: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: Input<'a>| {
+        let len = input.rest.find(|c: char| !predicate(c)).unwrap_or(input.rest.len());
+        if len == 0 {
+            return Err(input.expected(what));
+        }
+        Ok((&input.rest[..len], input.advance(len)))
+    }
+}
+
+fn number<'a, T: FromStr>(input: Input<'a>, len: usize, what: &str) -> PResult<'a, T> {
+    let digits = input.rest[..len].trim_start_matches(['-', '+']);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(input.expected(what));
+    }
+    let value = input.rest[..len].parse().map_err(|_| input.error(len, format!("{} out of range", what)))?;
+    Ok((value, input.advance(len)))
+}
+
+/**
+ * A run of digits
+ */
+pub fn unsigned<'a, T: FromStr>(what: &'static str) -> impl Parser<'a, T> {
+    move |input: Input<'a>| {
+        let len = input.rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(input.rest.len());
+        number(input, len, what)
+    }
+}
+
+/**
+ * A run of digits with an optional sign
+ */
+pub fn signed<'a, T: FromStr>(what: &'static str) -> impl Parser<'a, T> {
+    move |input: Input<'a>| {
+        let sign = usize::from(input.rest.starts_with(['-', '+']));
+        let len = input.rest[sign..].find(|c: char| !c.is_ascii_digit()).map_or(input.rest.len(), |len| sign + len);
+        number(input, len, what)
+    }
+}
+
+/**
+ * One of a fixed set of keywords, each standing for a value
+ */
+pub fn one_of<'a, T: Clone>(choices: &'static [(&'static str, T)], what: &'static str) -> impl Parser<'a, T> {
+    move |input: Input<'a>| {
+        choices.iter()
+            .find(|(keyword, _)| input.rest.starts_with(keyword))
+            .map(|(keyword, value)| (value.clone(), input.advance(keyword.len())))
+            .ok_or_else(|| input.expected(what))
+    }
+}
+
+/**
+ * One or more `item`s with a `separator` between each
+ */
+pub fn separated<'a, T, S>(item: impl Parser<'a, T>, separator: impl Parser<'a, S>) -> impl Parser<'a, Vec<T>> {
+    move |input: Input<'a>| {
+        let (first, mut rest) = item.parse(input)?;
+        let mut items = vec![first];
+        while let Ok((_, after_separator)) = separator.parse(rest) {
+            let (next, after_item) = item.parse(after_separator)?;
+            items.push(next);
+            rest = after_item;
+        }
+        Ok((items, rest))
+    }
+}
+
+fn end_of_line(input: Input<'_>) -> PResult<'_, ()> {
+    match input.rest.strip_prefix('\n') {
+        Some(_) => Ok(((), input.advance(1))),
+        None if input.is_empty() => Ok(((), input)),
+        None => Err(input.expected("the end of the line")),
+    }
+}
+
+/**
+ * Consecutive lines that each match `item` completely, stopping at a blank
+ * line or the end of the input
+ */
+pub fn lines<'a, T>(item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |input: Input<'a>| {
+        let mut items = Vec::new();
+        let mut rest = input;
+        while !rest.is_empty() && !rest.rest.starts_with('\n') {
+            let (value, after) = item.parse(rest)?;
+            let (_, after) = end_of_line(after)?;
+            items.push(value);
+            rest = after;
+        }
+        Ok((items, rest))
+    }
+}
+
+/**
+ * Groups separated by one or more blank lines, where `block` parses each
+ * group's lines, like `lines` does
+ */
+pub fn blocks<'a, T>(block: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |input: Input<'a>| {
+        let mut blocks = Vec::new();
+        let mut rest = input;
+        loop {
+            let blank = rest.rest.len() - rest.rest.trim_start_matches('\n').len();
+            rest = rest.advance(blank);
+            if rest.is_empty() {
+                return Ok((blocks, rest));
+            }
+            let (value, after) = block.parse(rest)?;
+            if !after.is_empty() && !after.rest.starts_with('\n') {
+                return Err(after.expected("a blank line"));
+            }
+            blocks.push(value);
+            rest = after;
+        }
+    }
+}
+
+/**
+ * Rows of single character cells, each turned into a value by `cell`. Every row
+ * must be the same width. Blank lines before the first row are skipped
+ */
+pub fn grid<'a, T>(cell: impl Fn(char) -> Option<T>, what: &'static str) -> impl Parser<'a, Vec<Vec<T>>> {
+    let row = move |input: Input<'a>| {
+        let mut values = Vec::new();
+        let mut rest = input;
+        while let Some(c) = rest.rest.chars().next().filter(|&c| c != '\n') {
+            values.push(cell(c).ok_or_else(|| rest.error(c.len_utf8(), format!("expected {}, found", what)))?);
+            rest = rest.advance(c.len_utf8());
+        }
+        Ok((values, rest))
+    };
+    let rows = lines(move |input: Input<'a>| {
+        let (values, rest) = row(input)?;
+        Ok(((values, input), rest))
+    });
+    move |input: Input<'a>| {
+        let input = input.advance(input.rest.len() - input.rest.trim_start_matches('\n').len());
+        let (rows, rest) = rows.parse(input)?;
+        if let Some(((first, _), _)) = rows.first().zip(rows.get(1)) {
+            let width = first.len();
+            if let Some((_, start)) = rows.iter().find(|(row, _)| row.len() != width) {
+                let row_len = start.rest.find('\n').unwrap_or(start.rest.len());
+                return Err(start.error(row_len, format!("expected every row to be {} wide, found", width)));
+            }
+        }
+        if rows.is_empty() {
+            return Err(input.expected("a grid"));
+        }
+        Ok((rows.into_iter().map(|(row, _)| row).collect(), rest))
+    }
+}
+
+/**
+ * A grid of decimal digits
+ */
+pub fn digit_grid<'a>() -> impl Parser<'a, Vec<Vec<u32>>> {
+    grid(|c| c.to_digit(10), "a digit")
+}
+
+/**
+ * A grid of any characters
+ */
+pub fn char_grid<'a>() -> impl Parser<'a, Vec<Vec<char>>> {
+    grid(Some, "a character")
+}
+
+/**
+ * Run `parser` over the whole of `input`, which may only have whitespace left over
+ */
+pub fn parse_all<'a, T>(day: u8, input: &'a str, parser: impl Parser<'a, T>) -> Result<T> {
+    finish(Input::new(day, input), parser)
+}
+
+/**
+ * Run `parser` over the whole of a line
+ */
+pub fn parse_line<'a, T>(line: &Line<'a>, parser: impl Parser<'a, T>) -> Result<T> {
+    finish(Input::from_line(line), parser)
+}
+
+fn finish<'a, T>(input: Input<'a>, parser: impl Parser<'a, T>) -> Result<T> {
+    let (value, rest) = parser.parse(input)?;
+    if !rest.rest.trim().is_empty() {
+        let rest = rest.advance(rest.rest.len() - rest.rest.trim_start().len());
+        return Err(rest.expected("the end of the input"));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Parser, blocks, digit_grid, lines, literal, one_of, parse_all, separated, signed, unsigned};
+
+    #[test]
+    fn test_numbers_and_lists() {
+        let items = literal("  Starting items: ").right(separated(unsigned::<u32>("an item"), literal(", ")));
+        assert_eq!(parse_all(11, "  Starting items: 79, 98\n", items).unwrap(), vec![79, 98]);
+        assert_eq!(parse_all(10, "-11", signed::<i32>("a value")).unwrap(), -11);
+        assert!(parse_all(10, "-", signed::<i32>("a value")).is_err());
+        assert!(parse_all(10, "300", unsigned::<u8>("a value")).is_err());
+    }
+
+    #[test]
+    fn test_keywords() {
+        const DIRECTIONS: &[(&str, (i32, i32))] = &[("U", (0, 1)), ("D", (0, -1)), ("L", (-1, 0)), ("R", (1, 0))];
+        let step = one_of(DIRECTIONS, "a direction").left(literal(" ")).then(unsigned::<u32>("a number of steps"));
+        let steps = parse_all(9, "R 4\nU 2\n", lines(step)).unwrap();
+        assert_eq!(steps, vec![((1, 0), 4), ((0, 1), 2)]);
+    }
+
+    #[test]
+    fn test_blocks() {
+        let elves = blocks(lines(unsigned::<u64>("calories")));
+        assert_eq!(parse_all(1, "1\n2\n\n\n3\n", elves).unwrap(), vec![vec![1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn test_error_location() {
+        let instruction = literal("move ").right(unsigned::<usize>("a number"))
+            .left(literal(" from ")).then(unsigned::<usize>("a stack"));
+        let error = parse_all(5, "move 1 from 2\nmove 1 from x\n", lines(instruction)).unwrap_err();
+        assert_eq!(error.to_string(),
+"day 5 input, line 2, column 13: expected a stack, found `x`
+  |
+2 | move 1 from x
+  |             ^");
+
+        let checked = unsigned::<u32>("a stack").try_map(|stack| if stack < 4 { Ok(stack) } else { Err("no such stack".to_string()) });
+        let error = parse_all(5, "12", checked).unwrap_err();
+        assert!(error.to_string().starts_with("day 5 input, line 1, column 1: no such stack `12`"), "{}", error);
+    }
+
+    #[test]
+    fn test_digit_grid() {
+        assert_eq!(parse_all(8, "303\n255\n", digit_grid()).unwrap(), vec![vec![3, 0, 3], vec![2, 5, 5]]);
+        let error = parse_all(8, "303\n25\n", digit_grid()).unwrap_err();
+        assert!(error.to_string().starts_with("day 8 input, line 2, column 1: expected every row to be 3 wide"), "{}", error);
+        assert!(parse_all(8, "3x3\n", digit_grid()).is_err());
+        assert_eq!(parse_all(8, "\n\n303\n", digit_grid()).unwrap(), vec![vec![3, 0, 3]]);
+    }
+}
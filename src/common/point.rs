@@ -0,0 +1,363 @@
+use std::{collections::HashMap, ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign}};
+
+/**
+ * A position on an unbounded board. `y` grows upwards, like a graph rather
+ * than the rows of a `Grid`
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+/**
+ * The offset between two points
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Vector {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point::new(0, 0);
+
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    pub fn manhattan_distance(self, other: Point) -> u64 {
+        (other - self).manhattan()
+    }
+
+    pub fn chebyshev_distance(self, other: Point) -> u64 {
+        (other - self).chebyshev()
+    }
+}
+
+impl Vector {
+    pub const ZERO: Vector = Vector::new(0, 0);
+
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    /**
+     * The vector with each component clamped to -1, 0 or 1, which is a
+     * single step (possibly diagonal) in the same direction
+     */
+    pub fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum())
+    }
+
+    /**
+     * The length if only horizontal and vertical moves are allowed
+     */
+    pub fn manhattan(self) -> u64 {
+        self.x.unsigned_abs() + self.y.unsigned_abs()
+    }
+
+    /**
+     * The length if diagonal moves are allowed too, like a king in chess
+     */
+    pub fn chebyshev(self) -> u64 {
+        self.x.unsigned_abs().max(self.y.unsigned_abs())
+    }
+}
+
+impl Add<Vector> for Point {
+    type Output = Point;
+
+    fn add(self, other: Vector) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign<Vector> for Point {
+    fn add_assign(&mut self, other: Vector) {
+        *self = *self + other;
+    }
+}
+
+impl Sub<Vector> for Point {
+    type Output = Point;
+
+    fn sub(self, other: Vector) -> Point {
+        self + -other
+    }
+}
+
+impl SubAssign<Vector> for Point {
+    fn sub_assign(&mut self, other: Vector) {
+        *self = *self - other;
+    }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+
+    fn sub(self, other: Point) -> Vector {
+        Vector::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, other: Vector) -> Vector {
+        Vector::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+
+    fn sub(self, other: Vector) -> Vector {
+        self + -other
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Vector {
+        Vector::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<i64> for Vector {
+    type Output = Vector;
+
+    fn mul(self, scale: i64) -> Vector {
+        Vector::new(self.x * scale, self.y * scale)
+    }
+}
+
+/**
+ * The four directions along the axes
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Heading {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Heading {
+    /// Clockwise from up
+    pub const ALL: [Heading; 4] = [Heading::Up, Heading::Right, Heading::Down, Heading::Left];
+
+    pub fn vector(self) -> Vector {
+        match self {
+            Heading::Up => Vector::new(0, 1),
+            Heading::Right => Vector::new(1, 0),
+            Heading::Down => Vector::new(0, -1),
+            Heading::Left => Vector::new(-1, 0),
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+}
+
+/**
+ * The eight directions including diagonals
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compass {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Compass {
+    /// Clockwise from north
+    pub const ALL: [Compass; 8] = [
+        Compass::North, Compass::NorthEast, Compass::East, Compass::SouthEast,
+        Compass::South, Compass::SouthWest, Compass::West, Compass::NorthWest,
+    ];
+
+    pub fn vector(self) -> Vector {
+        match self {
+            Compass::North => Vector::new(0, 1),
+            Compass::NorthEast => Vector::new(1, 1),
+            Compass::East => Vector::new(1, 0),
+            Compass::SouthEast => Vector::new(1, -1),
+            Compass::South => Vector::new(0, -1),
+            Compass::SouthWest => Vector::new(-1, -1),
+            Compass::West => Vector::new(-1, 0),
+            Compass::NorthWest => Vector::new(-1, 1),
+        }
+    }
+}
+
+impl From<Heading> for Compass {
+    fn from(heading: Heading) -> Self {
+        match heading {
+            Heading::Up => Compass::North,
+            Heading::Right => Compass::East,
+            Heading::Down => Compass::South,
+            Heading::Left => Compass::West,
+        }
+    }
+}
+
+/**
+ * An unbounded board that only stores the points that have been set, and
+ * keeps track of the smallest rectangle containing all of them
+ */
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    /// The bottom left and top right corners, if there are any cells
+    bounds: Option<(Point, Point)>,
+}
+
+/**
+ * Grow a bounding box to include `point`
+ */
+fn expand(bounds: Option<(Point, Point)>, point: Point) -> (Point, Point) {
+    match bounds {
+        None => (point, point),
+        Some((min, max)) => (
+            Point::new(min.x.min(point.x), min.y.min(point.y)),
+            Point::new(max.x.max(point.x), max.y.max(point.y)),
+        ),
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self { cells: HashMap::new(), bounds: None }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        self.bounds = Some(expand(self.bounds, point));
+        self.cells.insert(point, value)
+    }
+
+    pub fn remove(&mut self, point: Point) -> Option<T> {
+        let value = self.cells.remove(&point)?;
+        let on_edge = self.bounds.is_some_and(|(min, max)| {
+            point.x == min.x || point.x == max.x || point.y == min.y || point.y == max.y
+        });
+        if on_edge {
+            self.bounds = self.cells.keys().fold(None, |bounds, &point| Some(expand(bounds, point)));
+        }
+        Some(value)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.cells.get_mut(&point)
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /**
+     * The bottom left and top right corners of the smallest rectangle holding every cell
+     */
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        self.bounds
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Point, &T)> {
+        self.cells.iter()
+    }
+
+    /**
+     * Draw the bounding box as text with the highest `y` at the top, asking `f`
+     * for the character at each point
+     */
+    pub fn render(&self, f: impl Fn(Point, Option<&T>) -> char) -> String {
+        let Some((min, max)) = self.bounds else {
+            return String::new();
+        };
+        (min.y..=max.y).rev()
+            .map(|y| (min.x..=max.x).map(|x| {
+                let point = Point::new(x, y);
+                f(point, self.get(point))
+            }).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Compass, Heading, Point, SparseGrid, Vector};
+
+    #[test]
+    fn test_arithmetic() {
+        let head = Point::new(3, -1);
+        let tail = Point::new(1, 0);
+        let gap = head - tail;
+        assert_eq!(gap, Vector::new(2, -1));
+        assert_eq!(gap.signum(), Vector::new(1, -1));
+        assert_eq!(tail + gap.signum() * 2, Point::new(3, -2));
+        assert_eq!(head - gap, tail);
+        assert_eq!(head.manhattan_distance(tail), 3);
+        assert_eq!(head.chebyshev_distance(tail), 2);
+    }
+
+    #[test]
+    fn test_directions() {
+        assert_eq!(Heading::Up.turn_right(), Heading::Right);
+        assert_eq!(Heading::Up.turn_left(), Heading::Left);
+        assert_eq!(Heading::Left.opposite(), Heading::Right);
+        for heading in Heading::ALL {
+            assert_eq!(Compass::from(heading).vector(), heading.vector());
+        }
+        let total = Compass::ALL.iter().fold(Vector::ZERO, |total, direction| total + direction.vector());
+        assert_eq!(total, Vector::ZERO);
+    }
+
+    #[test]
+    fn test_sparse_grid() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.render(|_, _| '.'), "");
+        grid.insert(Point::ORIGIN, 's');
+        grid.insert(Point::new(2, 1), 'H');
+        grid.insert(Point::new(-1, 0), 'T');
+        assert_eq!(grid.bounds(), Some((Point::new(-1, 0), Point::new(2, 1))));
+        assert_eq!(grid.render(|_, c| c.copied().unwrap_or('.')), "...H\nTs..");
+
+        grid.remove(Point::new(2, 1));
+        assert_eq!(grid.bounds(), Some((Point::new(-1, 0), Point::ORIGIN)));
+        assert_eq!(grid.len(), 2);
+    }
+}
@@ -0,0 +1,273 @@
+use std::{cmp::Reverse, collections::{BinaryHeap, HashMap, HashSet, VecDeque}, hash::Hash};
+
+/**
+ * What a search found: the shortest distance to every node whose distance it
+ * settled, how it got there, the nodes it explored and the target it stopped
+ * at, if any. `bfs` settles a node as soon as it's discovered, so its
+ * distances include nodes still waiting to be explored when it stopped;
+ * `astar` and `dijkstra` only settle the nodes they explored
+ */
+#[derive(Debug, Clone)]
+pub struct Search<N> {
+    distances: HashMap<N, u64>,
+    previous: HashMap<N, N>,
+    explored: HashSet<N>,
+    target: Option<N>,
+}
+
+impl<N> Default for Search<N> {
+    fn default() -> Self {
+        Self { distances: HashMap::new(), previous: HashMap::new(), explored: HashSet::new(), target: None }
+    }
+}
+
+impl<N: Eq + Hash + Clone> Search<N> {
+    /**
+     * The first target the search reached, or `None` if none was reachable
+     */
+    pub fn target(&self) -> Option<&N> {
+        self.target.as_ref()
+    }
+
+    pub fn target_distance(&self) -> Option<u64> {
+        self.distance(self.target.as_ref()?)
+    }
+
+    pub fn distance(&self, node: &N) -> Option<u64> {
+        self.distances.get(node).copied()
+    }
+
+    /**
+     * The nodes from a start up to and including `node`
+     */
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        self.distances.get(node)?;
+        let mut path = vec![node.clone()];
+        while let Some(previous) = self.previous.get(path.last().unwrap()) {
+            path.push(previous.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /**
+     * The path from a start to the target
+     */
+    pub fn path(&self) -> Option<Vec<N>> {
+        self.path_to(self.target.as_ref()?)
+    }
+
+    /**
+     * Every node the search took off its queue, including the target, in no
+     * particular order. Nodes that were only discovered aren't included
+     */
+    pub fn explored(&self) -> impl Iterator<Item = &N> {
+        self.explored.iter()
+    }
+}
+
+/**
+ * Breadth first search from every start at once, where every edge costs 1.
+ * Stops at the first node matching `is_target`; pass `|_| false` to explore
+ * everything reachable
+ */
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut is_target: impl FnMut(&N) -> bool,
+) -> Search<N>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::default();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if !search.distances.contains_key(&start) {
+            search.distances.insert(start.clone(), 0);
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(node) = queue.pop_front() {
+        search.explored.insert(node.clone());
+        if is_target(&node) {
+            search.target = Some(node);
+            break;
+        }
+        let distance = search.distances[&node] + 1;
+        for next in neighbors(&node) {
+            if !search.distances.contains_key(&next) {
+                search.distances.insert(next.clone(), distance);
+                search.previous.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+    search
+}
+
+/**
+ * Dijkstra's algorithm from every start at once, where `neighbors` gives each
+ * neighbor with the cost of moving to it
+ */
+pub fn dijkstra<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbors: impl FnMut(&N) -> I,
+    is_target: impl FnMut(&N) -> bool,
+) -> Search<N>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    astar(starts, neighbors, |_| 0, is_target)
+}
+
+/**
+ * A* search, like `dijkstra` but guided by `heuristic`, an estimate of the
+ * remaining cost to a target. The estimate must never be too high, and since
+ * an explored node is never revisited it must also be consistent: it can't
+ * drop by more than the cost of a step from a node to its neighbor. Otherwise
+ * the path found may not be the shortest
+ */
+pub fn astar<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> u64,
+    mut is_target: impl FnMut(&N) -> bool,
+) -> Search<N>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    let mut search = Search::default();
+    // Nodes live here so the heap doesn't need them to be `Ord`
+    let mut nodes = Vec::new();
+    let mut queue = BinaryHeap::new();
+    let mut best: HashMap<N, u64> = HashMap::new();
+    let mut came_from: HashMap<N, N> = HashMap::new();
+
+    for start in starts {
+        if !best.contains_key(&start) {
+            best.insert(start.clone(), 0);
+            queue.push(Reverse((heuristic(&start), 0, nodes.len())));
+            nodes.push(start);
+        }
+    }
+
+    while let Some(Reverse((_, cost, index))) = queue.pop() {
+        let node = nodes[index].clone();
+        if search.distances.contains_key(&node) {
+            continue;
+        }
+        search.distances.insert(node.clone(), cost);
+        search.explored.insert(node.clone());
+        if let Some(previous) = came_from.remove(&node) {
+            search.previous.insert(node.clone(), previous);
+        }
+        if is_target(&node) {
+            search.target = Some(node);
+            break;
+        }
+
+        for (next, step) in neighbors(&node) {
+            let next_cost = cost + step;
+            if search.distances.contains_key(&next) || best.get(&next).is_some_and(|&known| known <= next_cost) {
+                continue;
+            }
+            best.insert(next.clone(), next_cost);
+            came_from.insert(next.clone(), node.clone());
+            queue.push(Reverse((next_cost + heuristic(&next), next_cost, nodes.len())));
+            nodes.push(next);
+        }
+    }
+    search
+}
+
+#[cfg(test)]
+mod test {
+    use crate::common::grid::{Cell, Grid, ORTHOGONAL};
+
+    use super::{astar, bfs, dijkstra};
+
+    fn heights() -> Grid<u32> {
+        Grid::parse(8, include_str!("../../fixtures/day8/example.input"), |c| c.to_digit(10), "a digit").unwrap()
+    }
+
+    /**
+     * Climb at most one higher per step, but go down any amount
+     */
+    fn climbs<'a>(grid: &'a Grid<u32>) -> impl FnMut(&Cell) -> Vec<Cell> + 'a {
+        |&cell| grid.neighbors(cell, &ORTHOGONAL).filter(|&next| grid[next] <= grid[cell] + 1).collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let grid = heights();
+        let search = bfs([(2, 0)], climbs(&grid), |&cell| cell == (0, 4));
+        assert_eq!(search.target(), Some(&(0, 4)));
+        assert_eq!(search.target_distance(), Some(6));
+        let path = search.path().unwrap();
+        assert_eq!(path.len(), 7);
+        assert_eq!(path[0], (2, 0));
+        assert!(path.windows(2).all(|step| grid[step[1]] <= grid[step[0]] + 1));
+    }
+
+    #[test]
+    fn test_unreachable_target() {
+        let grid = heights();
+        // Every tree next to the start is at least 3 higher
+        let search = bfs([(0, 1)], climbs(&grid), |&cell| cell == (4, 4));
+        assert_eq!(search.target(), None);
+        assert_eq!(search.path(), None);
+        assert_eq!(search.explored().count(), 1);
+
+        // The 9s around the bottom right corner wall it off
+        let search = bfs([(2, 0)], climbs(&grid), |&cell| cell == (4, 4));
+        assert_eq!(search.target(), None);
+        assert_eq!(search.explored().count(), 20);
+
+        let neighbors = |&cell: &Cell| climbs(&grid)(&cell).into_iter().map(|next| (next, 1)).collect::<Vec<_>>();
+        let search = dijkstra([(2, 0)], neighbors, |&cell| cell == (4, 4));
+        assert_eq!(search.target_distance(), None);
+        assert_eq!(search.distance(&(0, 4)), Some(6));
+    }
+
+    #[test]
+    fn test_multiple_starts() {
+        let grid = heights();
+        let zeros: Vec<Cell> = grid.iter().filter(|(_, &height)| height == 0).map(|(cell, _)| cell).collect();
+        assert_eq!(zeros, vec![(0, 1), (4, 4)]);
+
+        let search = bfs(zeros.clone(), |&cell| grid.neighbors(cell, &ORTHOGONAL).collect::<Vec<_>>(), |_| false);
+        assert_eq!(search.distance(&(0, 0)), Some(1));
+        assert_eq!(search.distance(&(4, 3)), Some(1));
+        assert_eq!(search.distance(&(2, 2)), Some(3));
+        assert_eq!(search.explored().count(), 25);
+        assert_eq!(search.path_to(&(3, 4)).unwrap(), vec![(4, 4), (3, 4)]);
+
+        // Stopping early leaves nodes that were discovered but not explored
+        let search = bfs([(0, 0)], |&cell| grid.neighbors(cell, &ORTHOGONAL).collect::<Vec<_>>(), |&cell| cell == (0, 1));
+        assert_eq!(search.distance(&(1, 0)), Some(1));
+        let explored: Vec<&Cell> = search.explored().collect();
+        assert!(!explored.contains(&&(1, 0)));
+        assert!(explored.contains(&&(0, 1)));
+    }
+
+    #[test]
+    fn test_weighted_searches_agree() {
+        let grid = heights();
+        // Moving onto a tree costs its height plus one
+        let neighbors = |&cell: &Cell| grid.neighbors(cell, &ORTHOGONAL).map(|next| (next, grid[next] as u64 + 1)).collect::<Vec<_>>();
+        let manhattan = |&(row, column): &Cell| (4 - row + 4 - column) as u64;
+
+        let slow = dijkstra([(0, 0)], neighbors, |&cell| cell == (4, 4));
+        let fast = astar([(0, 0)], neighbors, manhattan, |&cell| cell == (4, 4));
+        assert_eq!(slow.target_distance(), fast.target_distance());
+        assert!(fast.explored().count() <= slow.explored().count());
+
+        let path = fast.path().unwrap();
+        let cost: u64 = path[1..].iter().map(|&cell| grid[cell] as u64 + 1).sum();
+        assert_eq!(Some(cost), fast.target_distance());
+    }
+}
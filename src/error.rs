@@ -0,0 +1,146 @@
+use std::{fmt::Display, path::PathBuf};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/**
+ * Everything that can go wrong between reading a puzzle input and solving it
+ */
+#[derive(Debug)]
+pub enum Error {
+    /// The input couldn't be read
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The recorded answers file is malformed
+    Answers {
+        path: PathBuf,
+        reason: String,
+    },
+    /// The input couldn't be downloaded
+    Fetch {
+        url: String,
+        reason: String,
+    },
+    /// A new day couldn't be generated
+    Scaffold {
+        path: PathBuf,
+        reason: String,
+    },
+    /// A picture or animation couldn't be saved
+    Render {
+        path: PathBuf,
+        reason: String,
+    },
+    /// The input doesn't follow the puzzle's format
+    Parse {
+        day: u8,
+        /// 1 based line number within the input
+        line: usize,
+        /// 1 based column of `text` within the line
+        column: usize,
+        /// The text that couldn't be parsed
+        text: String,
+        /// The whole line containing `text`
+        context: String,
+        reason: String,
+    },
+}
+
+impl Error {
+    pub fn io(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        Error::Io { path: path.into(), source }
+    }
+
+    /**
+     * Build a parse error for `text`, which should be a slice of `context`,
+     * the `line`th line of the input. The column is taken from where `text`
+     * sits inside `context`, or the end of the line if it isn't a slice of it
+     */
+    pub fn parse(day: u8, line: usize, context: &str, text: &str, reason: impl Into<String>) -> Self {
+        Error::Parse {
+            day,
+            line,
+            column: column_of(context, text),
+            text: text.to_string(),
+            context: context.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+fn column_of(context: &str, text: &str) -> usize {
+    let start = context.as_ptr() as usize;
+    let offset = (text.as_ptr() as usize).wrapping_sub(start);
+    if offset <= context.len() {
+        context[..offset].chars().count() + 1
+    } else {
+        context.chars().count() + 1
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io { path, source } => write!(f, "couldn't read {}; {}", path.display(), source),
+            Error::Answers { path, reason } => write!(f, "invalid answers in {}; {}", path.display(), reason),
+            Error::Fetch { url, reason } => write!(f, "couldn't download {}; {}", url, reason),
+            Error::Scaffold { path, reason } => write!(f, "couldn't generate {}; {}", path.display(), reason),
+            Error::Render { path, reason } => write!(f, "couldn't draw {}; {}", path.display(), reason),
+            Error::Parse { day, line, column, text, context, reason } => {
+                if text.is_empty() {
+                    writeln!(f, "day {} input, line {}, column {}: {}", day, line, column, reason)?;
+                } else {
+                    writeln!(f, "day {} input, line {}, column {}: {} `{}`", day, line, column, reason, text)?;
+                }
+                let gutter = " ".repeat(line.to_string().len());
+                writeln!(f, "{} |", gutter)?;
+                writeln!(f, "{} | {}", line, context)?;
+                write!(f, "{} | {}{}", gutter, " ".repeat(column - 1), "^".repeat(text.chars().count().max(1)))
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Answers { .. } | Error::Fetch { .. } | Error::Scaffold { .. } | Error::Render { .. } | Error::Parse { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Error;
+
+    #[test]
+    fn test_parse_error_location() {
+        let context = "move 1 from x to 3";
+        let error = Error::parse(5, 12, context, &context[12..13], "expected a stack number");
+        match &error {
+            Error::Parse { line, column, text, .. } => {
+                assert_eq!(*line, 12);
+                assert_eq!(*column, 13);
+                assert_eq!(text, "x");
+            },
+            _ => panic!("expected a parse error"),
+        }
+        assert_eq!(error.to_string(),
+"day 5 input, line 12, column 13: expected a stack number `x`
+   |
+12 | move 1 from x to 3
+   |             ^");
+    }
+
+    #[test]
+    fn test_parse_error_missing_text() {
+        let context = "addx";
+        let error = Error::parse(10, 1, context, &context[4..], "expected a value");
+        match error {
+            Error::Parse { column, .. } => assert_eq!(column, 5),
+            _ => panic!("expected a parse error"),
+        }
+    }
+}
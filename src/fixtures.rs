@@ -0,0 +1,110 @@
+use std::{collections::BTreeMap, path::{Path, PathBuf}};
+
+use crate::common::read_file;
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Day};
+use crate::verify::{DayAnswers, Status};
+
+/**
+ * An example input from a puzzle's text along with the answers it should give.
+ * Fixtures live in `fixtures/dayN/`, as a `NAME.input` file per example and
+ * one `answers.toml` with a `[NAME]` table for each
+ */
+#[derive(Debug)]
+pub struct Fixture {
+    pub name: String,
+    pub input: String,
+    pub answers: DayAnswers,
+}
+
+#[derive(Debug)]
+pub struct FixtureReport {
+    pub fixture: String,
+    pub part: u8,
+    pub answer: Answer,
+    pub status: Status,
+}
+
+/**
+ * The directory holding a day's fixtures
+ */
+pub fn fixture_dir<P: AsRef<Path>>(root: P, day: u8) -> PathBuf {
+    root.as_ref().join(format!("day{}", day))
+}
+
+/**
+ * Load every fixture for a day from under `root`. A day without a fixture
+ * directory has no fixtures
+ */
+pub fn load_fixtures<P: AsRef<Path>>(root: P, day: u8) -> Result<Vec<Fixture>> {
+    let dir = fixture_dir(root, day);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let path = dir.join("answers.toml");
+    let answers: BTreeMap<String, DayAnswers> = toml::from_str(&read_file(&path)?)
+        .map_err(|why| Error::Answers { path: path.clone(), reason: why.message().to_string() })?;
+    answers.into_iter()
+        .map(|(name, answers)| {
+            let input = read_file(dir.join(format!("{}.input", name)))?;
+            Ok(Fixture { name, input, answers })
+        })
+        .collect()
+}
+
+/**
+ * Solve a fixture and compare both parts against its answers
+ */
+pub fn check_fixture(day: &Day, fixture: &Fixture) -> Result<[FixtureReport; 2]> {
+    let parsed = day.parse(&fixture.input)?;
+    let check = |part: u8| {
+        let (answer, expected) = match part {
+            1 => (day.part1(&parsed), &fixture.answers.part1),
+            _ => (day.part2(&parsed), &fixture.answers.part2),
+        };
+        let status = match expected {
+            None => Status::Missing,
+            Some(expected) if *expected == answer => Status::Pass,
+            Some(expected) => Status::Fail { expected: expected.clone() },
+        };
+        FixtureReport { fixture: fixture.name.clone(), part, answer, status }
+    };
+    Ok([check(1), check(2)])
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use crate::get_day;
+    use crate::verify::Status;
+
+    use super::{check_fixture, load_fixtures};
+
+    #[test]
+    fn test_load_and_check_fixtures() {
+        let root = env::temp_dir().join(format!("advent-of-code-fixtures-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("day6")).unwrap();
+        fs::write(root.join("day6/answers.toml"), "[short]\npart1 = 7\n\n[wrong]\npart1 = 1\npart2 = 19\n").unwrap();
+        fs::write(root.join("day6/short.input"), "mjqjpqmgbljsphdztnvjfqwrcgsmlb\n").unwrap();
+        fs::write(root.join("day6/wrong.input"), "mjqjpqmgbljsphdztnvjfqwrcgsmlb\n").unwrap();
+
+        let fixtures = load_fixtures(&root, 6).unwrap();
+        assert_eq!(fixtures.len(), 2);
+        assert!(load_fixtures(&root, 7).unwrap().is_empty());
+
+        let day = get_day(6).unwrap();
+        let [part1, part2] = check_fixture(day, &fixtures[0]).unwrap();
+        assert_eq!(part1.status, Status::Pass);
+        assert_eq!(part2.status, Status::Missing);
+        let [part1, part2] = check_fixture(day, &fixtures[1]).unwrap();
+        assert!(matches!(part1.status, Status::Fail { .. }));
+        assert_eq!(part2.status, Status::Pass);
+
+        fs::remove_file(root.join("day6/wrong.input")).unwrap();
+        assert!(load_fixtures(&root, 6).is_err());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,413 @@
+use std::{collections::HashSet, ops::RangeInclusive};
+
+/**
+ * A small seeded random number generator (SplitMix64). Kept in house rather
+ * than pulled in so the same seed always generates the same input
+ */
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /**
+     * A number in `range`, which mustn't be empty
+     */
+    pub fn range(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "can't pick from an empty range");
+        match (end - start).checked_add(1) {
+            Some(span) => start + self.next_u64() % span,
+            None => self.next_u64(),
+        }
+    }
+
+    /**
+     * An index into something `len` long, which mustn't be 0
+     */
+    pub fn index(&mut self, len: usize) -> usize {
+        self.range(0..=len as u64 - 1) as usize
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    /**
+     * True one time in `n`
+     */
+    pub fn one_in(&mut self, n: u64) -> bool {
+        self.range(1..=n) == 1
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+const LETTERS: [char; 52] = [
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z',
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
+];
+
+fn lowercase(rng: &mut Rng, length: RangeInclusive<u64>) -> String {
+    let length = rng.range(length);
+    (0..length).map(|_| *rng.pick(&LETTERS[..26])).collect()
+}
+
+/**
+ * `elves` elves each carrying a handful of snacks
+ */
+pub fn calories(rng: &mut Rng, elves: usize) -> String {
+    let elves: Vec<String> = (0..elves)
+        .map(|_| (0..rng.range(1..=15)).map(|_| format!("{}\n", rng.range(1000..=60000))).collect())
+        .collect();
+    elves.join("\n")
+}
+
+/**
+ * A guide of `rounds` rounds of rock paper scissors
+ */
+pub fn strategy_guide(rng: &mut Rng, rounds: usize) -> String {
+    (0..rounds).map(|_| format!("{} {}\n", rng.pick(&['A', 'B', 'C']), rng.pick(&['X', 'Y', 'Z']))).collect()
+}
+
+/**
+ * Three rucksacks whose compartments share exactly one item, and who all
+ * share exactly one badge
+ */
+fn rucksack_group(rng: &mut Rng) -> Vec<String> {
+    let badge = *rng.pick(&LETTERS);
+    let others: Vec<char> = LETTERS.iter().copied().filter(|&item| item != badge).collect();
+    // Every other item is left out of one of the rucksacks, so only the badge is in all three
+    let left_out: Vec<usize> = others.iter().map(|_| rng.index(3)).collect();
+    (0..3)
+        .map(|rucksack| {
+            let mut items: Vec<char> = others.iter()
+                .zip(&left_out)
+                .filter(|&(_, &left_out)| left_out != rucksack)
+                .map(|(&item, _)| item)
+                .collect();
+            rng.shuffle(&mut items);
+            let shared = items.pop().expect("a rucksack can hold most items");
+            // The rest are split so nothing else can be in both compartments
+            let (first_items, second_items) = items.split_at(items.len() / 2);
+            let size = rng.range(4..=16) as usize;
+            let mut first: Vec<char> = (0..size - 2).map(|_| *rng.pick(first_items)).chain([shared, badge]).collect();
+            let mut second: Vec<char> = (0..size - 1).map(|_| *rng.pick(second_items)).chain([shared]).collect();
+            if rng.one_in(2) {
+                std::mem::swap(&mut first, &mut second);
+            }
+            rng.shuffle(&mut first);
+            rng.shuffle(&mut second);
+            first.into_iter().chain(second).collect()
+        })
+        .collect()
+}
+
+/**
+ * At least `rucksacks` rucksacks, rounded up to whole groups of three elves
+ */
+pub fn rucksacks(rng: &mut Rng, rucksacks: usize) -> String {
+    (0..rucksacks.div_ceil(3))
+        .flat_map(|_| rucksack_group(rng))
+        .map(|rucksack| rucksack + "\n")
+        .collect()
+}
+
+/**
+ * `pairs` pairs of elves with their sections to clean
+ */
+pub fn section_pairs(rng: &mut Rng, pairs: usize) -> String {
+    let range = |rng: &mut Rng| {
+        let lower = rng.range(1..=99);
+        format!("{}-{}", lower, rng.range(lower..=99))
+    };
+    (0..pairs).map(|_| format!("{},{}\n", range(rng), range(rng))).collect()
+}
+
+/**
+ * Nine stacks of crates and `moves` moves, at least one, each taking crates
+ * from a stack that has them
+ */
+pub fn crates(rng: &mut Rng, moves: usize) -> String {
+    const STACKS: usize = 9;
+    let crates = (moves / 2).max(STACKS);
+    let mut stacks: Vec<Vec<char>> = vec![Vec::new(); STACKS];
+    for _ in 0..crates {
+        let stack = rng.index(STACKS);
+        stacks[stack].push(*rng.pick(&LETTERS[26..]));
+    }
+
+    let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+    let mut input = String::new();
+    for level in (0..height).rev() {
+        let row: Vec<String> = stacks.iter()
+            .map(|stack| stack.get(level).map_or("   ".to_string(), |c| format!("[{}]", c)))
+            .collect();
+        input += &row.join(" ");
+        input.push('\n');
+    }
+    let numbers: Vec<String> = (1..=STACKS).map(|number| format!(" {} ", number)).collect();
+    input += &numbers.join(" ");
+    input += "\n\n";
+
+    // Play the moves out so they only ever take crates that are there
+    for _ in 0..moves.max(1) {
+        let from = loop {
+            let from = rng.index(STACKS);
+            if !stacks[from].is_empty() {
+                break from;
+            }
+        };
+        let to = (from + rng.range(1..=STACKS as u64 - 1) as usize) % STACKS;
+        let count = rng.range(1..=stacks[from].len().min(30) as u64) as usize;
+        let at = stacks[from].len() - count;
+        let moved = stacks[from].split_off(at);
+        stacks[to].extend(moved);
+        input += &format!("move {} from {} to {}\n", count, from + 1, to + 1);
+    }
+    input
+}
+
+/**
+ * A datastream about `length` characters long whose start-of-packet marker
+ * ends halfway through and whose start-of-message marker is right at the end,
+ * so finding them means reading nearly all of it
+ */
+pub fn datastream(rng: &mut Rng, length: usize) -> String {
+    // Filler from too few letters to hold a marker. A run of the marker's
+    // first letter before it keeps windows reaching into the filler from
+    // looking like one
+    let filler = |rng: &mut Rng, letters: &[char], length: usize| -> String {
+        (0..length).map(|_| *rng.pick(letters)).collect()
+    };
+    let half = length / 2;
+    let mut stream = filler(rng, &LETTERS[..3], half.saturating_sub(7));
+    stream += "aaaabcd";
+    stream += &filler(rng, &LETTERS[..13], length.saturating_sub(half + 27));
+    stream += &"a".repeat(14);
+    stream += "bcdefghijklmn";
+    stream.push('\n');
+    stream
+}
+
+/**
+ * A directory of the file system a terminal session explores, with its
+ * children as indexes into the whole tree
+ */
+struct SessionDirectory {
+    name: String,
+    files: Vec<(u64, String)>,
+    children: Vec<usize>,
+}
+
+/**
+ * List `directory` and then explore each of its children in turn
+ */
+fn explore(tree: &[SessionDirectory], directory: usize, rng: &mut Rng, session: &mut Vec<String>) {
+    session.push("$ ls".to_string());
+    let directory = &tree[directory];
+    let mut listing: Vec<String> = directory.files.iter().map(|(size, name)| format!("{} {}", size, name))
+        .chain(directory.children.iter().map(|&child| format!("dir {}", tree[child].name)))
+        .collect();
+    rng.shuffle(&mut listing);
+    session.extend(listing);
+    for &child in &directory.children {
+        session.push(format!("$ cd {}", tree[child].name));
+        explore(tree, child, rng, session);
+        session.push("$ cd ..".to_string());
+    }
+}
+
+/**
+ * A terminal session exploring a file system of `directories` directories
+ * besides the root
+ */
+pub fn terminal_session(rng: &mut Rng, directories: usize) -> String {
+    let mut tree: Vec<SessionDirectory> = Vec::with_capacity(directories + 1);
+    for index in 0..=directories {
+        let files = (0..rng.range(0..=4))
+            .map(|_| (rng.range(1000..=300_000), lowercase(rng, 1..=8) + *rng.pick(&[".txt", ".dat", ""])))
+            .collect();
+        tree.push(SessionDirectory { name: String::new(), files, children: Vec::new() });
+        if index > 0 {
+            let parent = rng.index(index);
+            let siblings: HashSet<&str> = tree[parent].children.iter().map(|&child| tree[child].name.as_str()).collect();
+            let name = loop {
+                let name = lowercase(rng, 1..=8);
+                if !siblings.contains(name.as_str()) {
+                    break name;
+                }
+            };
+            tree[index].name = name;
+            tree[parent].children.push(index);
+        }
+    }
+
+    let mut session = vec!["$ cd /".to_string()];
+    explore(&tree, 0, rng, &mut session);
+    session.join("\n") + "\n"
+}
+
+/**
+ * A square forest `side` trees across, at least one
+ */
+pub fn tree_grid(rng: &mut Rng, side: usize) -> String {
+    let side = side.max(1);
+    (0..side)
+        .map(|_| (0..side).map(|_| char::from(b'0' + rng.range(0..=9) as u8)).collect::<String>() + "\n")
+        .collect()
+}
+
+/**
+ * `motions` moves of the head of the rope
+ */
+pub fn rope_motions(rng: &mut Rng, motions: usize) -> String {
+    (0..motions).map(|_| format!("{} {}\n", rng.pick(&['R', 'L', 'U', 'D']), rng.range(1..=19))).collect()
+}
+
+/**
+ * A program of `instructions` instructions that keeps the sprite around the
+ * screen
+ */
+pub fn cpu_program(rng: &mut Rng, instructions: usize) -> String {
+    let mut x: i64 = 1;
+    (0..instructions)
+        .map(|_| {
+            if rng.one_in(3) {
+                return "noop\n".to_string();
+            }
+            // Lean back towards the middle of the screen
+            let value = rng.range(0..=20) as i64 - 10 + if x < 5 { 5 } else if x > 35 { -5 } else { 0 };
+            x += value;
+            format!("addx {}\n", value)
+        })
+        .collect()
+}
+
+/// Primes to test divisibility by. Their product still fits in a worry level
+const DIVISORS: [u64; 15] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47];
+
+/**
+ * Monkeys holding `items` items between them. There's a monkey for every
+ * four items, from 2 up to 15 monkeys
+ */
+pub fn monkeys(rng: &mut Rng, items: usize) -> String {
+    let count = (items / 4).clamp(2, DIVISORS.len());
+    let mut divisors = DIVISORS[..count].to_vec();
+    rng.shuffle(&mut divisors);
+    let mut held: Vec<Vec<u64>> = vec![Vec::new(); count];
+    for _ in 0..items {
+        let monkey = rng.index(count);
+        held[monkey].push(rng.range(50..=99));
+    }
+    let squarer = rng.index(count);
+
+    let monkeys: Vec<String> = (0..count)
+        .map(|id| {
+            let operation = if id == squarer {
+                "* old".to_string()
+            } else if rng.one_in(3) {
+                format!("* {}", rng.range(2..=19))
+            } else {
+                format!("+ {}", rng.range(1..=8))
+            };
+            let other = |rng: &mut Rng| (id + rng.range(1..=count as u64 - 1) as usize) % count;
+            let items: Vec<String> = held[id].iter().map(u64::to_string).collect();
+            format!(
+                "Monkey {}:\n  Starting items: {}\n  Operation: new = old {}\n  Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey {}\n",
+                id, items.join(", "), operation, divisors[id], other(rng), other(rng),
+            )
+        })
+        .collect();
+    monkeys.join("\n")
+}
+
+/**
+ * A valid input for `day` from `seed`, or `None` if the day has no generator.
+ * What `size` counts depends on the day: elves, rounds, rucksacks, pairs,
+ * moves, characters, directories, trees across, motions, instructions and
+ * items for days 1 to 11
+ */
+pub fn generate(day: u8, seed: u64, size: usize) -> Option<String> {
+    let generator: fn(&mut Rng, usize) -> String = match day {
+        1 => calories,
+        2 => strategy_guide,
+        3 => rucksacks,
+        4 => section_pairs,
+        5 => crates,
+        6 => datastream,
+        7 => terminal_session,
+        8 => tree_grid,
+        9 => rope_motions,
+        10 => cpu_program,
+        11 => monkeys,
+        _ => return None,
+    };
+    Some(generator(&mut Rng::new(seed), size))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{DAYS, q6::Q6, solution::Solution};
+
+    use super::{datastream, generate, Rng};
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(7);
+        let numbers: Vec<u64> = (0..100).map(|_| rng.range(3..=5)).collect();
+        assert!(numbers.iter().all(|n| (3..=5).contains(n)));
+        assert!((3..=5).all(|n| numbers.contains(&n)));
+        assert_eq!(Rng::new(7).next_u64(), Rng::new(7).next_u64());
+        assert_ne!(Rng::new(7).next_u64(), Rng::new(8).next_u64());
+
+        let mut items: Vec<u32> = (0..20).collect();
+        rng.shuffle(&mut items);
+        items.sort_unstable();
+        assert_eq!(items, (0..20).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn test_generated_inputs_are_valid() {
+        // A newly scaffolded day has no generator yet, so there's nothing to check
+        for day in DAYS.iter().filter(|day| generate(day.day, 1, 1).is_some()) {
+            for (seed, size) in [(1, 1), (2, 10), (3, 200)] {
+                let input = generate(day.day, seed, size).unwrap();
+                let parsed = day.parse(&input).unwrap_or_else(|why| panic!("seed {} size {}: {}", seed, size, why));
+                day.part1(&parsed);
+                day.part2(&parsed);
+                assert_eq!(generate(day.day, seed, size).unwrap(), input);
+            }
+            assert!(generate(day.day, 1, 100).unwrap().len() > generate(day.day, 1, 10).unwrap().len());
+        }
+        assert!(generate(26, 1, 10).is_none());
+    }
+
+    #[test]
+    fn test_datastream_markers() {
+        for length in [0, 30, 1000] {
+            let stream = Q6::parse(&datastream(&mut Rng::new(length as u64), length)).unwrap();
+            let half = length / 2;
+            let packet = half.saturating_sub(7) + 7;
+            assert_eq!(Q6::part1(&stream), packet);
+            assert_eq!(Q6::part2(&stream), stream.len());
+            assert!(stream.len() >= length);
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use std::{fs, io::Read, path::{Path, PathBuf}};
+
+use crate::common::read_file;
+use crate::error::{Error, Result};
+use crate::input_file_name;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const DEFAULT_YEAR: u16 = 2022;
+
+/**
+ * The part of an HTTP client the input provider needs, so tests can swap in
+ * something that doesn't touch the network
+ */
+pub trait HttpClient {
+    /**
+     * GET `url` with the session cookie and return the body of a successful response
+     */
+    fn get(&self, url: &str, session: &str) -> std::result::Result<String, String>;
+}
+
+/**
+ * The default client, backed by `ureq`
+ */
+pub struct UreqClient {
+    agent: ureq::Agent,
+}
+
+impl Default for UreqClient {
+    fn default() -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .user_agent(concat!("advent-of-code/", env!("CARGO_PKG_VERSION")))
+                .build(),
+        }
+    }
+}
+
+impl HttpClient for UreqClient {
+    fn get(&self, url: &str, session: &str) -> std::result::Result<String, String> {
+        let response = self.agent
+            .get(url)
+            .set("Cookie", &format!("session={}", session))
+            .call()
+            .map_err(|why| match why {
+                ureq::Error::Status(code, response) => format!("server responded {} {}", code, response.status_text()),
+                ureq::Error::Transport(transport) => transport.to_string(),
+            })?;
+        let mut body = String::new();
+        response.into_reader().read_to_string(&mut body).map_err(|why| why.to_string())?;
+        Ok(body)
+    }
+}
+
+/**
+ * Finds the input for a day, downloading it into a cache directory the first
+ * time it's needed
+ */
+pub struct InputProvider<C: HttpClient = UreqClient> {
+    client: C,
+    base_url: String,
+    year: u16,
+    cache_dir: PathBuf,
+    session: Option<String>,
+    refresh: bool,
+}
+
+impl InputProvider<UreqClient> {
+    pub fn new<P: Into<PathBuf>>(cache_dir: P) -> Self {
+        Self::with_client(UreqClient::default(), cache_dir)
+    }
+}
+
+impl<C: HttpClient> InputProvider<C> {
+    pub fn with_client<P: Into<PathBuf>>(client: C, cache_dir: P) -> Self {
+        Self {
+            client,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            year: DEFAULT_YEAR,
+            cache_dir: cache_dir.into(),
+            session: None,
+            refresh: false,
+        }
+    }
+
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn year(mut self, year: u16) -> Self {
+        self.year = year;
+        self
+    }
+
+    pub fn session(mut self, session: Option<String>) -> Self {
+        self.session = session;
+        self
+    }
+
+    /**
+     * Download inputs even when they're already cached, keeping the cached
+     * copy if the download fails
+     */
+    pub fn refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    pub fn cache_path(&self, day: u8) -> PathBuf {
+        self.cache_dir.join(input_file_name(day))
+    }
+
+    pub fn url(&self, day: u8) -> String {
+        format!("{}/{}/day/{}/input", self.base_url, self.year, day)
+    }
+
+    /**
+     * Get the input for a day from the cache, or the server when it isn't cached
+     */
+    pub fn input(&self, day: u8) -> Result<String> {
+        let path = self.cache_path(day);
+        let cached = path.exists();
+        if cached && !self.refresh {
+            return read_file(&path);
+        }
+
+        match self.download(day, &path) {
+            Ok(input) => Ok(input),
+            Err(_) if cached => read_file(&path),
+            Err(why) => Err(why),
+        }
+    }
+
+    fn download(&self, day: u8, path: &Path) -> Result<String> {
+        let url = self.url(day);
+        let session = self.session.as_deref().ok_or_else(|| Error::Fetch {
+            url: url.clone(),
+            reason: "no session token was given".to_string(),
+        })?;
+        let input = self.client
+            .get(&url, session)
+            .map_err(|reason| Error::Fetch { url: url.clone(), reason })?;
+
+        fs::create_dir_all(&self.cache_dir).map_err(|why| Error::io(&self.cache_dir, why))?;
+        fs::write(path, &input).map_err(|why| Error::io(path, why))?;
+        Ok(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::Cell, env, fs, io::{Read, Write}, net::TcpListener, path::PathBuf, thread};
+
+    use super::{HttpClient, InputProvider};
+
+    struct FakeClient {
+        response: Option<&'static str>,
+        requests: Cell<usize>,
+    }
+
+    impl HttpClient for FakeClient {
+        fn get(&self, _url: &str, _session: &str) -> Result<String, String> {
+            self.requests.set(self.requests.get() + 1);
+            self.response.map(String::from).ok_or_else(|| "offline".to_string())
+        }
+    }
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("advent-of-code-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_downloads_then_caches() {
+        let dir = cache_dir("cache");
+        let client = FakeClient { response: Some("1\n2\n"), requests: Cell::new(0) };
+        let provider = InputProvider::with_client(client, &dir).session(Some("token".to_string()));
+
+        assert_eq!(provider.input(1).unwrap(), "1\n2\n");
+        assert_eq!(provider.input(1).unwrap(), "1\n2\n");
+        assert_eq!(provider.client.requests.get(), 1);
+        assert_eq!(fs::read_to_string(dir.join("puzzle_1.input")).unwrap(), "1\n2\n");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_falls_back_to_cache_offline() {
+        let dir = cache_dir("offline");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("puzzle_2.input"), "A Y\n").unwrap();
+        let client = FakeClient { response: None, requests: Cell::new(0) };
+        let provider = InputProvider::with_client(client, &dir)
+            .session(Some("token".to_string()))
+            .refresh(true);
+
+        assert_eq!(provider.input(2).unwrap(), "A Y\n");
+        assert_eq!(provider.client.requests.get(), 1);
+        assert!(provider.input(3).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_requires_session_to_download() {
+        let dir = cache_dir("session");
+        let client = FakeClient { response: Some("1\n"), requests: Cell::new(0) };
+        let provider = InputProvider::with_client(client, &dir);
+
+        assert!(provider.input(1).is_err());
+        assert_eq!(provider.client.requests.get(), 0);
+    }
+
+    #[test]
+    fn test_ureq_client_against_stub_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let read = stream.read(&mut buffer).unwrap();
+                if read == 0 { break; }
+                request.extend_from_slice(&buffer[..read]);
+            }
+            let body = "noop\naddx 3\n";
+            write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body).unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let dir = cache_dir("stub");
+        let provider = InputProvider::new(&dir)
+            .base_url(&format!("http://{}/", address))
+            .year(2022)
+            .session(Some("secret".to_string()));
+        assert_eq!(provider.input(10).unwrap(), "noop\naddx 3\n");
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2022/day/10/input HTTP/1.1\r\n"), "{}", request);
+        assert!(request.contains("session=secret"), "{}", request);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,4 +1,24 @@
-mod common;
+pub mod animate;
+
+pub mod bench;
+
+pub mod common;
+
+pub mod error;
+
+pub mod fixtures;
+
+pub mod generate;
+
+pub mod input;
+
+pub mod render;
+
+pub mod scaffold;
+
+pub mod solution;
+
+pub mod verify;
 
 pub mod q1;
 
@@ -21,3 +41,51 @@ pub mod q9;
 pub mod q10;
 
 pub mod q11;
+
+pub use error::{Error, Result};
+use solution::Day;
+
+/**
+ * Every solved day, in calendar order
+ */
+pub static DAYS: &[Day] = &[
+    Day::new::<q1::Q1>(),
+    Day::new::<q2::Q2>(),
+    Day::new::<q3::Q3>(),
+    Day::new::<q4::Q4>(),
+    Day::new::<q5::Q5>(),
+    Day::new::<q6::Q6>(),
+    Day::new::<q7::Q7>(),
+    Day::new::<q8::Q8>(),
+    Day::new::<q9::Q9>(),
+    Day::new::<q10::Q10>(),
+    Day::new::<q11::Q11>(),
+];
+
+/**
+ * Look up a day in the registry by its number
+ */
+pub fn get_day(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+/**
+ * The file name of the puzzle input for a day
+ */
+pub fn input_file_name(day: u8) -> String {
+    format!("puzzle_{}.input", day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DAYS, get_day};
+
+    #[test]
+    fn test_days_are_registered_in_order() {
+        let days: Vec<u8> = DAYS.iter().map(|day| day.day).collect();
+        assert_eq!(days[..11], (1..=11).collect::<Vec<u8>>());
+        assert!(days.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(days.iter().all(|&day| get_day(day).unwrap().day == day));
+        assert!(get_day(26).is_none());
+    }
+}
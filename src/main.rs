@@ -1,29 +1,538 @@
-use advent_of_code::{q1, q2, q3, q4, q5, q6, q7, q8, q9, q10, q11};
-
-fn main() {
-    println!("Q1:");
-    q1::run(1);
-    q1::run(3);
-    println!("Q2:");
-    q2::run1();
-    q2::run2();
-    println!("Q3:");
-    q3::run();
-    println!("Q4:");
-    q4::run();
-    println!("Q5:");
-    q5::run();
-    q5::run2();
-    println!("Q6:");
-    q6::run();
-    println!("Q7:");
-    q7::solve_q7();
-    println!("Q8:");
-    q8::solve_q8();
-    println!("Q9:");
-    q9::solve_q9();
-    println!("Q10:");
-    q10::solve_q10();
-    println!("Q11:");
-    q11::solve_q11();
+use std::{io::Read, ops::RangeInclusive, path::PathBuf, process::ExitCode, time::Duration};
+
+use advent_of_code::{
+    DAYS, Error, get_day, input_file_name,
+    animate::play,
+    bench::{benchmark_day, Stats},
+    common::read_file,
+    generate::generate,
+    input::{DEFAULT_BASE_URL, DEFAULT_YEAR, InputProvider},
+    q5, q8, q9, q10,
+    render::{save_gif, save_png, Image},
+    scaffold::new_day,
+    solution::{Answer, Day},
+    verify::{Answers, Status, verify_day_in},
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+
+#[derive(Parser)]
+#[command(version, about = "Advent of Code solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve one or more days
+    Run(RunArgs),
+    /// Check every day against the recorded answers
+    Verify(VerifyArgs),
+    /// Time parsing and solving each day
+    Bench(BenchArgs),
+    /// Download puzzle inputs that aren't cached yet
+    Fetch(FetchArgs),
+    /// Generate and register the module for a new day
+    NewDay(NewDayArgs),
+    /// Draw a day's puzzle as a PNG, or animate it as a GIF
+    Render(RenderArgs),
+    /// Step through a day's simulation in the terminal
+    Animate(AnimateArgs),
+    /// Generate a random puzzle input, as large as you like
+    Generate(GenerateArgs),
+}
+
+#[derive(Args, Default)]
+struct RunArgs {
+    /// A day (`5`) or an inclusive range of days (`1-5`). Runs every day when omitted
+    #[arg(value_parser = parse_days)]
+    days: Option<RangeInclusive<u8>>,
+
+    /// Which part(s) to solve
+    #[arg(short, long, value_enum, default_value_t = PartSelection::Both)]
+    part: PartSelection,
+
+    /// Read the input from this file instead of the day's default input. Use `-` for stdin
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+
+    /// Directory holding the `puzzle_N.input` files
+    #[arg(long, default_value = ".")]
+    input_dir: PathBuf,
+
+    /// How to print the answers
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+struct VerifyArgs {
+    /// A day (`5`) or an inclusive range of days (`1-5`). Verifies every day when omitted
+    #[arg(value_parser = parse_days)]
+    days: Option<RangeInclusive<u8>>,
+
+    /// The file holding the accepted answers
+    #[arg(short, long, default_value = "answers.toml")]
+    answers: PathBuf,
+
+    /// Directory holding the `puzzle_N.input` files
+    #[arg(long, default_value = ".")]
+    input_dir: PathBuf,
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    /// A day (`5`) or an inclusive range of days (`1-5`). Benchmarks every day when omitted
+    #[arg(value_parser = parse_days)]
+    days: Option<RangeInclusive<u8>>,
+
+    /// How many times to parse and solve each day
+    #[arg(short = 'n', long, default_value_t = 10)]
+    iterations: usize,
+
+    /// Directory holding the `puzzle_N.input` files
+    #[arg(long, default_value = ".")]
+    input_dir: PathBuf,
+
+    /// How to print the timings
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Benchmark a generated input of this size instead of the puzzle input.
+    /// See `generate` for what the size counts on each day
+    #[arg(long)]
+    generate: Option<usize>,
+
+    /// Seed for the generated inputs
+    #[arg(long, default_value_t = 0, requires = "generate")]
+    seed: u64,
+}
+
+#[derive(Args)]
+struct FetchArgs {
+    /// A day (`5`) or an inclusive range of days (`1-5`). Fetches every day when omitted
+    #[arg(value_parser = parse_days)]
+    days: Option<RangeInclusive<u8>>,
+
+    /// The session cookie of a logged in account. Defaults to `$AOC_SESSION`
+    #[arg(long)]
+    session: Option<String>,
+
+    /// Directory to cache the `puzzle_N.input` files in
+    #[arg(long, default_value = ".")]
+    cache_dir: PathBuf,
+
+    /// Server to download from. Defaults to `$AOC_BASE_URL`, then the Advent of Code site
+    #[arg(long)]
+    base_url: Option<String>,
+
+    /// The year of the puzzles
+    #[arg(long, default_value_t = DEFAULT_YEAR)]
+    year: u16,
+
+    /// Download inputs again even if they're cached
+    #[arg(long)]
+    refresh: bool,
+}
+
+#[derive(Args)]
+struct NewDayArgs {
+    /// The day to generate
+    day: u8,
+
+    /// The crate root holding `src/lib.rs`
+    #[arg(long, default_value = ".")]
+    root: PathBuf,
+
+    /// Directory to create the empty `puzzle_N.input` in
+    #[arg(long, default_value = ".")]
+    input_dir: PathBuf,
+}
+
+#[derive(Args)]
+struct RenderArgs {
+    /// The day to draw: 8 (scenic trees), 9 (the rope) or 10 (the CRT)
+    day: u8,
+
+    /// Where to save the picture. A `.gif` is animated, anything else is saved
+    /// as a PNG of the final frame
+    #[arg(short, long)]
+    output: PathBuf,
+
+    /// Read the input from this file instead of the day's default input. Use `-` for stdin
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+
+    /// Directory holding the `puzzle_N.input` files
+    #[arg(long, default_value = ".")]
+    input_dir: PathBuf,
+
+    /// How many pixels wide and high to draw each cell
+    #[arg(long, default_value_t = 8)]
+    scale: usize,
+
+    /// Hundredths of a second to show each frame of a GIF
+    #[arg(long, default_value_t = 5)]
+    delay: u16,
+
+    /// How many knots the rope has on day 9
+    #[arg(long, default_value_t = 10)]
+    knots: usize,
+}
+
+#[derive(Args)]
+struct AnimateArgs {
+    /// The day to play: 5 (the crane), 9 (the rope) or 10 (the CRT)
+    day: u8,
+
+    /// Which part's simulation to play on day 5 (the CrateMover 9000 or 9001)
+    /// and day 9 (a rope of 2 or 10 knots)
+    #[arg(short, long, value_enum, default_value_t = Part::Two)]
+    part: Part,
+
+    /// Read the input from this file instead of the day's default input. Use `-` for stdin
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+
+    /// Directory holding the `puzzle_N.input` files
+    #[arg(long, default_value = ".")]
+    input_dir: PathBuf,
+
+    /// Milliseconds to show each step for
+    #[arg(long, default_value_t = 200)]
+    delay: u64,
+
+    /// Start paused on the first step
+    #[arg(long)]
+    paused: bool,
+}
+
+#[derive(Args)]
+struct GenerateArgs {
+    /// The day to generate an input for
+    day: u8,
+
+    /// How big an input to generate. Counts elves, rounds, rucksacks, pairs,
+    /// moves, characters, directories, trees across, motions, instructions and
+    /// items for days 1 to 11
+    #[arg(short, long, default_value_t = 1000)]
+    size: usize,
+
+    /// The same seed always generates the same input
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Where to save the input. Printed when omitted
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Part {
+    #[value(name = "1")]
+    One,
+    #[value(name = "2")]
+    Two,
+}
+
+#[derive(Clone, Copy, PartialEq, Default, ValueEnum)]
+enum PartSelection {
+    #[value(name = "1")]
+    One,
+    #[value(name = "2")]
+    Two,
+    #[default]
+    Both,
+}
+
+impl PartSelection {
+    fn includes(&self, part: u8) -> bool {
+        match self {
+            PartSelection::One => part == 1,
+            PartSelection::Two => part == 2,
+            PartSelection::Both => true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Serialize)]
+struct PartResult {
+    day: u8,
+    part: u8,
+    answer: Answer,
+}
+
+fn parse_days(s: &str) -> Result<RangeInclusive<u8>, String> {
+    let parse_day = |day: &str| {
+        day.trim().parse::<u8>().map_err(|_| format!("`{}` is not a day", day))
+    };
+    let range = match s.split_once('-') {
+        Some((first, last)) => parse_day(first)?..=parse_day(last)?,
+        None => {
+            let day = parse_day(s)?;
+            day..=day
+        }
+    };
+    if range.is_empty() {
+        return Err(format!("`{}` is an empty range of days", s));
+    }
+    Ok(range)
+}
+
+fn read_input(path: &PathBuf) -> Result<String, Error> {
+    if path.as_os_str() == "-" {
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|why| Error::io("<stdin>", why))?;
+        Ok(input)
+    } else {
+        read_file(path)
+    }
+}
+
+fn select_days(days: &Option<RangeInclusive<u8>>) -> Result<Vec<&'static Day>, String> {
+    match days {
+        None => Ok(DAYS.iter().collect()),
+        Some(range) => range.clone()
+            .map(|day| get_day(day).ok_or_else(|| format!("day {} has not been solved", day)))
+            .collect(),
+    }
+}
+
+fn print_answer(part: u8, answer: &Answer) {
+    let answer = answer.to_string();
+    if answer.contains('\n') {
+        println!("Part {}:\n{}", part, answer);
+    } else {
+        println!("Part {}: {}", part, answer);
+    }
+}
+
+fn run(args: RunArgs) -> Result<(), String> {
+    let days = select_days(&args.days)?;
+    if args.input.is_some() && days.len() > 1 {
+        return Err("--input can only be used when running a single day".to_string());
+    }
+
+    let mut results = Vec::new();
+    for day in days {
+        let path = args.input.clone()
+            .unwrap_or_else(|| args.input_dir.join(input_file_name(day.day)));
+        let input = read_input(&path).map_err(|why| why.to_string())?;
+        let parsed = day.parse(&input).map_err(|why| why.to_string())?;
+
+        if let OutputFormat::Text = args.format {
+            println!("Q{}:", day.day);
+        }
+        for part in [1, 2] {
+            if !args.part.includes(part) {
+                continue;
+            }
+            let answer = if part == 1 { day.part1(&parsed) } else { day.part2(&parsed) };
+            match args.format {
+                OutputFormat::Text => print_answer(part, &answer),
+                OutputFormat::Json => results.push(PartResult { day: day.day, part, answer }),
+            }
+        }
+    }
+
+    if let OutputFormat::Json = args.format {
+        println!("{}", serde_json::to_string_pretty(&results).map_err(|why| why.to_string())?);
+    }
+    Ok(())
+}
+
+/**
+ * Only the first line of multi-line answers fits in the verify table
+ */
+fn summarize(answer: &Answer) -> String {
+    let answer = answer.to_string();
+    match answer.split_once('\n') {
+        Some((first, _)) => format!("{}...", first),
+        None => answer,
+    }
+}
+
+fn verify(args: VerifyArgs) -> Result<(), String> {
+    let days = select_days(&args.days)?;
+    let answers = Answers::load(&args.answers).map_err(|why| why.to_string())?;
+
+    let mut failures = 0;
+    println!("{:>3} {:>4}  {:<7} {:>12}  answer", "day", "part", "status", "time");
+    for day in days {
+        let reports = match verify_day_in(day, &args.input_dir, &answers) {
+            Ok(reports) => reports,
+            Err(why) => {
+                failures += 1;
+                println!("{:>3} {:>4}  {:<7} {}", day.day, "-", "ERROR", why);
+                continue;
+            }
+        };
+        for report in reports {
+            println!("{:>3} {:>4}  {:<7} {:>12}  {}",
+                report.day, report.part, report.status.to_string(), format!("{:.2?}", report.elapsed), summarize(&report.answer));
+            if let Status::Fail { expected } = &report.status {
+                failures += 1;
+                println!("{:>31}expected {}", "", summarize(expected));
+            }
+        }
+    }
+
+    if failures > 0 {
+        return Err(format!("{} day(s) or part(s) failed verification", failures));
+    }
+    Ok(())
+}
+
+fn bench(args: BenchArgs) -> Result<(), String> {
+    let days = select_days(&args.days)?;
+
+    let mut benchmarks = Vec::new();
+    if let OutputFormat::Text = args.format {
+        println!("{:>3}  {:<6} {:>12} {:>12} {:>12}", "day", "phase", "min", "median", "mean");
+    }
+    for day in days {
+        let input = match args.generate {
+            Some(size) => generate(day.day, args.seed, size)
+                .ok_or_else(|| format!("day {} has no input generator", day.day))?,
+            None => read_input(&args.input_dir.join(input_file_name(day.day))).map_err(|why| why.to_string())?,
+        };
+        let benchmark = benchmark_day(day, &input, args.iterations).map_err(|why| why.to_string())?;
+        if let OutputFormat::Text = args.format {
+            let phases: [(&str, &Stats); 3] = [("parse", &benchmark.parse), ("part1", &benchmark.part1), ("part2", &benchmark.part2)];
+            for (phase, stats) in phases {
+                println!("{:>3}  {:<6} {:>12} {:>12} {:>12}", benchmark.day, phase,
+                    format!("{:.2?}", stats.min), format!("{:.2?}", stats.median), format!("{:.2?}", stats.mean));
+            }
+        }
+        benchmarks.push(benchmark);
+    }
+
+    if let OutputFormat::Json = args.format {
+        println!("{}", serde_json::to_string_pretty(&benchmarks).map_err(|why| why.to_string())?);
+    }
+    Ok(())
+}
+
+fn fetch(args: FetchArgs) -> Result<(), String> {
+    let days = select_days(&args.days)?;
+    let session = args.session.or_else(|| std::env::var("AOC_SESSION").ok());
+    let base_url = args.base_url
+        .or_else(|| std::env::var("AOC_BASE_URL").ok())
+        .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+    let provider = InputProvider::new(&args.cache_dir)
+        .base_url(&base_url)
+        .year(args.year)
+        .session(session)
+        .refresh(args.refresh);
+
+    for day in days {
+        let input = provider.input(day.day).map_err(|why| why.to_string())?;
+        println!("day {}: {} ({} lines)", day.day, provider.cache_path(day.day).display(), input.lines().count());
+    }
+    Ok(())
+}
+
+fn scaffold(args: NewDayArgs) -> Result<(), String> {
+    let written = new_day(&args.root, &args.input_dir, args.day).map_err(|why| why.to_string())?;
+    for path in written {
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn draw(args: RenderArgs) -> Result<(), String> {
+    let path = args.input.clone()
+        .unwrap_or_else(|| args.input_dir.join(input_file_name(args.day)));
+    let input = read_input(&path).map_err(|why| why.to_string())?;
+    // Frames are drawn, scaled and written one at a time, since a long
+    // animation blown up to a visible size won't all fit in memory
+    let frames: Box<dyn Iterator<Item = Image>> = match args.day {
+        8 => q8::scenic_heatmap(&input).map(|image| Box::new(std::iter::once(image)) as Box<dyn Iterator<Item = Image>>),
+        9 => q9::rope_frames(&input, args.knots).map(|frames| Box::new(frames) as Box<dyn Iterator<Item = Image>>),
+        10 => q10::crt_frames(&input).map(|frames| Box::new(frames.into_iter()) as Box<dyn Iterator<Item = Image>>),
+        day => return Err(format!("day {} can't be drawn; try 8, 9 or 10", day)),
+    }.map_err(|why| why.to_string())?;
+    let scale = args.scale.max(1);
+
+    let animated = args.output.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("gif"));
+    if animated {
+        save_gif(&args.output, frames.map(|frame| frame.scale(scale)), args.delay)
+    } else {
+        save_png(&args.output, &frames.last().expect("every day draws at least one frame").scale(scale))
+    }.map_err(|why| why.to_string())?;
+    println!("wrote {}", args.output.display());
+    Ok(())
+}
+
+fn animate(args: AnimateArgs) -> Result<(), String> {
+    let path = args.input.clone()
+        .unwrap_or_else(|| args.input_dir.join(input_file_name(args.day)));
+    let input = read_input(&path).map_err(|why| why.to_string())?;
+    let part2 = args.part == Part::Two;
+    let frames = match args.day {
+        5 => q5::crane_frames(&input, part2),
+        9 => q9::rope_steps(&input, if part2 { 10 } else { 2 }),
+        10 => q10::crt_steps(&input),
+        day => return Err(format!("day {} can't be animated; try 5, 9 or 10", day)),
+    }.map_err(|why| why.to_string())?;
+    play(frames, Duration::from_millis(args.delay), args.paused).map_err(|why| why.to_string())
+}
+
+fn generate_input(args: GenerateArgs) -> Result<(), String> {
+    let input = generate(args.day, args.seed, args.size)
+        .ok_or_else(|| format!("day {} has no input generator", args.day))?;
+    match args.output {
+        Some(path) => {
+            std::fs::write(&path, input).map_err(|why| Error::io(&path, why).to_string())?;
+            println!("wrote {}", path.display());
+        },
+        None => print!("{}", input),
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Some(Command::Run(args)) => run(args),
+        Some(Command::Verify(args)) => verify(args),
+        Some(Command::Bench(args)) => bench(args),
+        Some(Command::Fetch(args)) => fetch(args),
+        Some(Command::NewDay(args)) => scaffold(args),
+        Some(Command::Render(args)) => draw(args),
+        Some(Command::Animate(args)) => animate(args),
+        Some(Command::Generate(args)) => generate_input(args),
+        None => run(RunArgs { input_dir: PathBuf::from("."), ..RunArgs::default() }),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(why) => {
+            eprintln!("error: {}", why);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_days;
+
+    #[test]
+    fn test_parse_days() {
+        assert_eq!(parse_days("5"), Ok(5..=5));
+        assert_eq!(parse_days("1-11"), Ok(1..=11));
+        assert!(parse_days("7-3").is_err());
+        assert!(parse_days("x").is_err());
+    }
 }
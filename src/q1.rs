@@ -1,66 +1,241 @@
-use crate::common;
-
-pub fn run(n: usize) {
-    let input_path = "./puzzle_1.input";
-    let numbers = read_file(input_path);
-
-    let mut sum = 0;
-    let mut sums = Vec::with_capacity(numbers.len());
-    for number in numbers {
-        if number == 0 {
-            sums.push(sum);
-            sum = 0;
-        } else {
-            sum += number;
-        }
+use std::{cmp::Reverse, collections::{BTreeMap, BinaryHeap}, fmt::Display};
+
+use crate::common::{blocks, Line};
+use crate::error::Result;
+use crate::solution::Solution;
+
+/**
+ * The Calories of each item one elf carries, in the order they're listed
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elf {
+    pub items: Vec<u64>,
+}
+
+impl Elf {
+    /**
+     * An elf from its block of lines, one item each
+     */
+    fn from_lines(lines: &[Line]) -> Result<Self> {
+        let items = lines.iter()
+            .map(|line| line.parse(line.text.trim(), "a number of Calories"))
+            .collect::<Result<_>>()?;
+        Ok(Self { items })
+    }
+
+    pub fn total(&self) -> u64 {
+        self.items.iter().fold(0, |sum: u64, &item| sum.saturating_add(item))
+    }
+
+    pub fn largest_item(&self) -> Option<u64> {
+        self.items.iter().max().copied()
+    }
+}
+
+pub struct Q1;
+
+impl Solution for Q1 {
+    const DAY: u8 = 1;
+    type Input = Vec<Elf>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_elves(input)
+    }
+
+    fn part1(elves: &Self::Input) -> u64 {
+        most_calories(elves, 1)
+    }
+
+    fn part2(elves: &Self::Input) -> u64 {
+        most_calories(elves, 3)
+    }
+}
+
+/**
+ * The most Calories carried by a single elf
+ */
+pub fn part1(input: &str) -> Result<u64> {
+    Ok(Q1::part1(&Q1::parse(input)?))
+}
+
+/**
+ * The total Calories carried by the top three elves
+ */
+pub fn part2(input: &str) -> Result<u64> {
+    Ok(Q1::part2(&Q1::parse(input)?))
+}
+
+/**
+ * The total Calories each elf carries, in the order they're listed
+ */
+pub fn elf_totals(elves: &[Elf]) -> Vec<u64> {
+    elves.iter().map(Elf::total).collect()
+}
+
+/**
+ * The `n` elves carrying the most Calories as (index, total), most first and
+ * the earlier elf first on a tie. Only `n` elves are kept at a time, in a
+ * min-heap whose smallest is replaced by anyone carrying more, so this takes
+ * O(elves * log n) rather than sorting every elf
+ */
+pub fn top_elves(totals: &[u64], n: usize) -> Vec<(usize, u64)> {
+    if n == 0 {
+        return Vec::new();
     }
-    sums.sort_unstable();
-    for diff in 0..n {
-        let index = (sums.len() - 1) - diff;
-        sum += sums.get(index).unwrap();
+    // Ordered so the heap's top is the elf to drop next: the smallest total,
+    // then the latest elf
+    let mut heap: BinaryHeap<Reverse<(u64, Reverse<usize>)>> = BinaryHeap::with_capacity(n + 1);
+    for (index, &total) in totals.iter().enumerate() {
+        heap.push(Reverse((total, Reverse(index))));
+        if heap.len() > n {
+            heap.pop();
+        }
     }
-    println!("The most Calories Raised by {} elf(s) is: {}", n, sum);
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((total, Reverse(index)))| (index, total))
+        .collect()
 }
 
-fn read_file(input: &str) -> Vec<u64>{
-    let s = common::read_file(input);
+fn most_calories(elves: &[Elf], n: usize) -> u64 {
+    top_elves(&elf_totals(elves), n).iter().fold(0, |sum, &(_, total)| sum.saturating_add(total))
+}
 
-    let numbers: Vec<u64> = parse_input(&s);
+/**
+ * A summary of how many Calories the elves carry
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalorieStats {
+    pub elves: usize,
+    pub total: u64,
+    pub min: u64,
+    pub max: u64,
+    pub mean: f64,
+    pub median: f64,
+}
 
-    numbers
+impl CalorieStats {
+    /**
+     * Summarize the elves' totals, or `None` if there are no elves
+     */
+    pub fn from_totals(totals: &[u64]) -> Option<Self> {
+        let mut sorted = totals.to_vec();
+        sorted.sort_unstable();
+        let (&min, &max) = (sorted.first()?, sorted.last()?);
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] as f64 + sorted[middle] as f64) / 2.0
+        } else {
+            sorted[middle] as f64
+        };
+        let total = sorted.iter().fold(0, |sum: u64, &total| sum.saturating_add(total));
+        Some(Self {
+            elves: sorted.len(),
+            total,
+            min,
+            max,
+            mean: total as f64 / sorted.len() as f64,
+            median,
+        })
+    }
+}
+
+impl Display for CalorieStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} elves carrying {} Calories: min {}, max {}, mean {:.1}, median {:.1}",
+            self.elves, self.total, self.min, self.max, self.mean, self.median)
+    }
 }
 
-fn parse_input(s: &str) -> Vec<u64> {
-    s.split('\n').map(|subs| {
-        match subs.trim_matches(' ').parse::<u64>() { Ok(i) => i, _ => 0}
-    } ).collect()
+/**
+ * How many elves carry each range of Calories, as the start of each range
+ * `width` wide and its count. Ranges nobody falls in are left out
+ */
+pub fn distribution(totals: &[u64], width: u64) -> Vec<(u64, usize)> {
+    let width = width.max(1);
+    let mut buckets = BTreeMap::new();
+    for &total in totals {
+        *buckets.entry(total / width * width).or_insert(0) += 1;
+    }
+    buckets.into_iter().collect()
 }
 
+/**
+ * Read the elves, each a block of lines with one item per line. Only blank
+ * lines separate elves, so an item of 0 Calories is still an item
+ */
+fn parse_elves(input: &str) -> Result<Vec<Elf>> {
+    blocks(Q1::DAY, input).map(|block| Elf::from_lines(&block)).collect()
+}
+
+#[cfg(test)]
 mod test {
-    use super::parse_input;
+    use super::{distribution, elf_totals, parse_elves, top_elves, CalorieStats};
+
+    const EXAMPLE: &str = include_str!("../fixtures/day1/example.input");
 
     #[test]
-    fn test_parse_input() {
-        let input_1 = "1
-                                2
-                                3
-                                4";
-        let expected_output_1 = vec![1,2,3,4];
+    fn test_parse_elves() {
+        let input = "1
+                     2
+                     3
+
+                     4
 
-        assert_eq!(parse_input(input_1), expected_output_1);
+                     0
+                     6";
+        let elves = parse_elves(input).unwrap();
+        let items: Vec<&[u64]> = elves.iter().map(|elf| elf.items.as_slice()).collect();
+        assert_eq!(items, vec![&[1, 2, 3][..], &[4], &[0, 6]]);
+        assert_eq!(elves[0].largest_item(), Some(3));
 
-        let input_2 = "1
-        2
-        3
+        // The last elf counts without a blank line after it, and extra blank
+        // lines don't make empty elves
+        assert_eq!(elf_totals(&parse_elves("\n\n1\n2\n\n\n3").unwrap()), vec![3, 3]);
+        assert!(parse_elves("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse_elves("1000\n2000\n\n12a4\n").unwrap_err();
+        assert_eq!(error.to_string(),
+"day 1 input, line 4, column 1: expected a number of Calories, found `12a4`
+  |
+4 | 12a4
+  | ^^^^");
+        assert!(parse_elves("1\n-5").is_err());
+    }
 
-        4
+    #[test]
+    fn test_elf_totals() {
+        let totals = elf_totals(&parse_elves(EXAMPLE).unwrap());
+        assert_eq!(totals, vec![6000, 4000, 11000, 24000, 10000]);
+        assert!(elf_totals(&[]).is_empty());
+    }
 
-        5
+    #[test]
+    fn test_top_elves() {
+        let totals = elf_totals(&parse_elves(EXAMPLE).unwrap());
+        assert_eq!(top_elves(&totals, 3), vec![(3, 24000), (2, 11000), (4, 10000)]);
+        assert_eq!(top_elves(&totals, 10).len(), 5);
+        assert!(top_elves(&totals, 0).is_empty());
 
-        6";
+        // Ties go to the earlier elf
+        assert_eq!(top_elves(&[5, 7, 5, 7, 5], 3), vec![(1, 7), (3, 7), (0, 5)]);
+    }
 
-        let expected_output_2 = vec![1,2,3,0,4,0,5,0,6];
+    #[test]
+    fn test_stats() {
+        let totals = elf_totals(&parse_elves(EXAMPLE).unwrap());
+        let stats = CalorieStats::from_totals(&totals).unwrap();
+        assert_eq!((stats.elves, stats.total, stats.min, stats.max), (5, 55000, 4000, 24000));
+        assert_eq!((stats.mean, stats.median), (11000.0, 10000.0));
+        assert_eq!(CalorieStats::from_totals(&[1, 4]).unwrap().median, 2.5);
+        assert!(CalorieStats::from_totals(&[]).is_none());
+        assert_eq!(stats.to_string(), "5 elves carrying 55000 Calories: min 4000, max 24000, mean 11000.0, median 10000.0");
 
-        assert_eq!(parse_input(input_2), expected_output_2);
+        assert_eq!(distribution(&totals, 10000), vec![(0, 2), (10000, 2), (20000, 1)]);
     }
 }
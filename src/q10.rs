@@ -1,32 +1,66 @@
-use std::collections::VecDeque;
+use std::{collections::VecDeque, fmt::Display};
 
-use crate::common::{read_file, parse_lines};
+use crate::animate::Frame;
+use crate::common::{grid::Grid, numbered_lines, Line};
+use crate::error::Result;
+use crate::render::{Image, Rgb};
+use crate::solution::Solution;
 
+const CRT_WIDTH: u32 = 40;
+const CRT_HEIGHT: u32 = 6;
+
+const LIT: Rgb = [120, 255, 120];
+const DARK: Rgb = [10, 25, 10];
+const SPRITE: Rgb = [40, 90, 40];
+/// The beam while it lights the pixel it's drawing
+const BEAM_LIT: Rgb = [255, 255, 255];
+/// The beam while the sprite is somewhere else
+const BEAM_DARK: Rgb = [220, 40, 40];
+
+#[derive(Clone)]
 enum Operation {
     Add(i64),
     Noop
 }
 
-struct Instruction {
+#[derive(Clone)]
+pub struct Instruction {
     num_cycles: u32,
     operation: Operation,
 }
 
-impl From<&str> for Instruction {
-    fn from(line: &str) -> Self {
-        let bytes = line.as_bytes();
-        if bytes == b"noop" {
-            Instruction {
-                num_cycles: 1,
-                operation: Operation::Noop
-            }
-        } else {
-            let chunks: Vec<&[u8]> = bytes.split(|&c| c == b' ').collect();
-            let val = String::from_utf8(chunks[1].to_vec()).unwrap().parse::<i64>().unwrap();
-            Instruction {
-                num_cycles: 2,
-                operation: Operation::Add(val)
-            }
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.operation {
+            Operation::Add(value) => write!(f, "addx {}", value),
+            Operation::Noop => write!(f, "noop"),
+        }
+    }
+}
+
+impl Instruction {
+    pub fn from_line(line: &Line) -> Result<Self> {
+        match line.token(0, "an instruction")? {
+            "noop" => {
+                if line.text != "noop" {
+                    return Err(line.error(line.text, "expected `noop` with no operand, found"));
+                }
+                Ok(Instruction {
+                    num_cycles: 1,
+                    operation: Operation::Noop
+                })
+            },
+            "addx" => {
+                let val = line.parse(line.token(1, "a value to add")?, "a value to add")?;
+                if line.token(2, "").is_ok() {
+                    return Err(line.error(line.text, "expected `addx <value>`, found"));
+                }
+                Ok(Instruction {
+                    num_cycles: 2,
+                    operation: Operation::Add(val)
+                })
+            },
+            instruction => Err(line.error(instruction, "expected `noop` or `addx`, found"))
         }
     }
 }
@@ -65,7 +99,7 @@ impl CpuEmulator {
     }
 
     pub fn has_instructions(&self) -> bool {
-        self.instructions.len() > 0
+        !self.instructions.is_empty()
     }
 
     pub fn execute_clock_cycle(&mut self) {
@@ -75,7 +109,7 @@ impl CpuEmulator {
             if instruction.num_cycles == 0 {
                 match instruction.operation {
                     Operation::Add(amount) => {
-                        self.register_x += amount;
+                        self.register_x = self.register_x.saturating_add(amount);
                     },
                     Operation::Noop => {}
                 }
@@ -87,229 +121,217 @@ impl CpuEmulator {
         }
 
         self.current_cycle += 1;
-        if self.current_cycle == 20 || (self.current_cycle > 20 && ((self.current_cycle - 20) % 40) == 0) {
-            self.signal_strength += self.register_x * (self.current_cycle as i64);
+        if self.current_cycle == 20 || (self.current_cycle > 20 && (self.current_cycle - 20).is_multiple_of(CRT_WIDTH)) {
+            let strength = self.register_x.saturating_mul(self.current_cycle as i64);
+            self.signal_strength = self.signal_strength.saturating_add(strength);
         }
     }
 
-    pub fn print_crt_data(&self) {
-        println!("register x: {}, current_cycle: {}", self.register_x, self.current_cycle % 40);
-    }
-
-    pub fn draw_crt_pixel(&self) {
-        if self.register_x == (((self.current_cycle - 1) % 40) as i64)
-        || self.register_x + 1 == (((self.current_cycle - 1) % 40) as i64)
-        || self.register_x - 1 == (((self.current_cycle - 1) % 40) as i64) {
-            print!("#");
+    /**
+     * The pixel the CRT draws during the current cycle
+     */
+    pub fn crt_pixel(&self) -> char {
+        let position = ((self.current_cycle - 1) % CRT_WIDTH) as i64;
+        if self.register_x.abs_diff(position) <= 1 {
+            '#'
         } else {
-            print!(".");
+            '.'
         }
-        if (self.current_cycle) % 40 == 0 {
-            println!();
+    }
+}
+
+pub struct Q10;
+
+impl Solution for Q10 {
+    const DAY: u8 = 10;
+    type Input = Vec<Instruction>;
+    type Answer1 = i64;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        numbered_lines(Q10::DAY, input)
+            .map(|line| Instruction::from_line(&line))
+            .collect()
+    }
+
+    fn part1(instructions: &Self::Input) -> i64 {
+        let mut emulator = CpuEmulator::default();
+        emulator.insert_instructions(instructions.clone());
+        while emulator.has_instructions() && emulator.get_current_cycle() < 220 {
+            emulator.execute_clock_cycle();
         }
+        emulator.get_signal_strength()
+    }
+
+    fn part2(instructions: &Self::Input) -> String {
+        render_crt(instructions)
     }
 }
 
-pub fn solve_q10() {
-    let input_data = read_file("./puzzle_10.input");
-    let input: Vec<Instruction> = parse_lines(&input_data)
-        .into_iter()
-        .map(|line| Instruction::from(line))
-        .collect();
+/**
+ * Run the program for one full screen refresh and return the image drawn on the CRT
+ */
+fn render_crt(instructions: &[Instruction]) -> String {
+    let mut emulator = CpuEmulator::default();
+    emulator.insert_instructions(instructions.to_vec());
+    let mut screen = Grid::new(CRT_WIDTH as usize, CRT_HEIGHT as usize, '.');
+    for row in 0..screen.height() {
+        for column in 0..screen.width() {
+            screen[(row, column)] = emulator.crt_pixel();
+            emulator.execute_clock_cycle();
+        }
+    }
+    screen.to_string()
+}
 
+/**
+ * Pictures of the CRT during each cycle of a screen refresh, followed by the
+ * finished screen. Each shows the pixels drawn so far, the beam, and where the
+ * sprite is on the rest of the row
+ */
+pub fn crt_frames(input: &str) -> Result<Vec<Image>> {
     let mut emulator = CpuEmulator::default();
-    emulator.insert_instructions(input);
-    while emulator.has_instructions() && emulator.get_current_cycle() < 220 {
-        emulator.execute_clock_cycle();
+    emulator.insert_instructions(Q10::parse(input)?);
+    let mut screen = Image::new(CRT_WIDTH as usize, CRT_HEIGHT as usize, DARK);
+    let mut frames = Vec::new();
+    for row in 0..screen.height() {
+        for column in 0..screen.width() {
+            let lit = emulator.crt_pixel() == '#';
+            screen.set(column, row, if lit { LIT } else { DARK });
+
+            let mut frame = screen.clone();
+            let sprite = (emulator.register_x.saturating_sub(1)..=emulator.register_x.saturating_add(1))
+                .filter_map(|position| usize::try_from(position).ok())
+                .filter(|&position| position > column);
+            for position in sprite {
+                frame.set(position, row, SPRITE);
+            }
+            frame.set(column, row, if lit { BEAM_LIT } else { BEAM_DARK });
+            frames.push(frame);
+            emulator.execute_clock_cycle();
+        }
     }
-    println!("");
-    println!("Solution1: {}", emulator.get_signal_strength());
-    let input: Vec<Instruction> = parse_lines(&input_data)
-        .into_iter()
-        .map(|line| Instruction::from(line))
-        .collect();
+    frames.push(screen);
+    Ok(frames)
+}
 
+/**
+ * The CRT drawn as text during each cycle of a screen refresh. Under the
+ * screen are the sprite and the beam, to show why each pixel is lit or not
+ */
+pub fn crt_steps(input: &str) -> Result<Vec<Frame>> {
     let mut emulator = CpuEmulator::default();
-    emulator.insert_instructions(input);
-    while emulator.has_instructions() {
-        emulator.draw_crt_pixel();
-        emulator.execute_clock_cycle();
+    emulator.insert_instructions(Q10::parse(input)?);
+    let mut screen = Grid::new(CRT_WIDTH as usize, CRT_HEIGHT as usize, ' ');
+    let mut frames = Vec::new();
+    for row in 0..screen.height() {
+        for column in 0..screen.width() {
+            screen[(row, column)] = emulator.crt_pixel();
+            let sprite: String = (0..CRT_WIDTH as i64)
+                .map(|position| if emulator.register_x.abs_diff(position) <= 1 { '#' } else { '.' })
+                .collect();
+            let beam = format!("{}^", " ".repeat(column));
+            let running = emulator.current_instruction.as_ref().map_or("nothing".to_string(), Instruction::to_string);
+            let status = format!("cycle {}, X = {}, running {}", emulator.get_current_cycle(), emulator.register_x, running);
+            frames.push(Frame::new(format!("{}\n\n{}\n{}", screen, sprite, beam), status));
+            emulator.execute_clock_cycle();
+        }
     }
-    println!();
+    Ok(frames)
 }
 
+/**
+ * The sum of the signal strengths during the 20th, 60th, ... 220th cycles
+ */
+pub fn part1(input: &str) -> Result<i64> {
+    Ok(Q10::part1(&Q10::parse(input)?))
+}
+
+/**
+ * The image drawn on the CRT
+ */
+pub fn part2(input: &str) -> Result<String> {
+    Ok(Q10::part2(&Q10::parse(input)?))
+}
+
+#[cfg(test)]
 mod test {
-    use crate::common::parse_lines;
+    use crate::common::numbered_lines;
+    use crate::error::Result;
 
-    use super::{Instruction, CpuEmulator};
+    use super::{Instruction, CpuEmulator, crt_frames, crt_steps, render_crt, BEAM_LIT, DARK, LIT, SPRITE};
+
+    #[test]
+    fn test_instruction_errors() {
+        for input in ["addy 4", "addx", "addx x", "noop 3", "addx 1 2"] {
+            let line = numbered_lines(10, input).next().unwrap();
+            assert!(Instruction::from_line(&line).is_err(), "{}", input);
+        }
+
+        for input in ["noop 3", "noop "] {
+            let line = numbered_lines(10, input).next().unwrap();
+            let error = Instruction::from_line(&line).err().unwrap().to_string();
+            assert!(error.contains("expected `noop` with no operand, found"), "{}", error);
+        }
+    }
 
     #[test]
     fn test_example() {
-let input = "addx 15
-addx -11
-addx 6
-addx -3
-addx 5
-addx -1
-addx -8
-addx 13
-addx 4
-noop
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx -35
-addx 1
-addx 24
-addx -19
-addx 1
-addx 16
-addx -11
-noop
-noop
-addx 21
-addx -15
-noop
-noop
-addx -3
-addx 9
-addx 1
-addx -3
-addx 8
-addx 1
-addx 5
-noop
-noop
-noop
-noop
-noop
-addx -36
-noop
-addx 1
-addx 7
-noop
-noop
-noop
-addx 2
-addx 6
-noop
-noop
-noop
-noop
-noop
-addx 1
-noop
-noop
-addx 7
-addx 1
-noop
-addx -13
-addx 13
-addx 7
-noop
-addx 1
-addx -33
-noop
-noop
-noop
-addx 2
-noop
-noop
-noop
-addx 8
-noop
-addx -1
-addx 2
-addx 1
-noop
-addx 17
-addx -9
-addx 1
-addx 1
-addx -3
-addx 11
-noop
-noop
-addx 1
-noop
-addx 1
-noop
-noop
-addx -13
-addx -19
-addx 1
-addx 3
-addx 26
-addx -30
-addx 12
-addx -1
-addx 3
-addx 1
-noop
-noop
-noop
-addx -9
-addx 18
-addx 1
-addx 2
-noop
-noop
-addx 9
-noop
-noop
-noop
-addx -1
-addx 2
-addx -37
-addx 1
-addx 3
-noop
-addx 15
-addx -21
-addx 22
-addx -6
-addx 1
-noop
-addx 2
-addx 1
-noop
-addx -10
-noop
-noop
-addx 20
-addx 1
-addx 2
-addx 2
-addx -6
-addx -11
-noop
-noop
-noop";
-        let input: Vec<Instruction> = parse_lines(input)
-            .into_iter()
-            .map(|line| Instruction::from(line))
-            .collect();
+        let input = include_str!("../fixtures/day10/example.input");
+        let input: Vec<Instruction> = numbered_lines(10, input)
+            .map(|line| Instruction::from_line(&line))
+            .collect::<Result<_>>()
+            .unwrap();
 
         let mut emulator = CpuEmulator::default();
-        emulator.insert_instructions(input);
+        emulator.insert_instructions(input.clone());
         while emulator.has_instructions() && emulator.get_current_cycle() < 220 {
-            emulator.draw_crt_pixel();
             emulator.execute_clock_cycle();
-            // emulator.print_crt_data();
         }
         assert_eq!(emulator.get_signal_strength(), 13140);
-        while emulator.has_instructions() {
-            emulator.draw_crt_pixel();
-            emulator.execute_clock_cycle();
-            // emulator.print_crt_data();
 
+        let expected_image = "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....";
+        assert_eq!(render_crt(&input), expected_image);
+    }
+
+    #[test]
+    fn test_crt_frames() {
+        let input = include_str!("../fixtures/day10/example.input");
+        let frames = crt_frames(input).unwrap();
+        assert_eq!(frames.len(), 241);
+
+        // The sprite starts over the first three pixels
+        assert_eq!(frames[0].get(0, 0), Some(BEAM_LIT));
+        assert_eq!(frames[0].get(1, 0), Some(SPRITE));
+        assert_eq!(frames[0].get(3, 0), Some(DARK));
+
+        let expected_image = render_crt(&numbered_lines(10, input)
+            .map(|line| Instruction::from_line(&line))
+            .collect::<Result<Vec<Instruction>>>()
+            .unwrap());
+        let screen = frames.last().unwrap();
+        for (row, line) in expected_image.lines().enumerate() {
+            for (column, pixel) in line.chars().enumerate() {
+                assert_eq!(screen.get(column, row), Some(if pixel == '#' { LIT } else { DARK }));
+            }
         }
-        println!();
+    }
+
+    #[test]
+    fn test_crt_steps() {
+        let frames = crt_steps(include_str!("../fixtures/day10/example.input")).unwrap();
+        assert_eq!(frames.len(), 240);
+        assert_eq!(frames[0].status, "cycle 1, X = 1, running addx 15");
+        assert_eq!(frames[2].status, "cycle 3, X = 16, running addx -11");
+
+        let lines: Vec<&str> = frames[2].picture.lines().collect();
+        assert_eq!(lines[0].trim_end(), "##.");
+        assert_eq!(lines[7], "...............###......................");
+        assert_eq!(lines[8], "  ^");
     }
 }
@@ -2,12 +2,15 @@ use std::fmt::Display;
 
 use regex::{Regex, Captures};
 
-use crate::common::read_file;
+use crate::common::{blocks, Line};
+use crate::common::cycle::find_cycle_within;
+use crate::error::{Error, Result};
+use crate::solution::Solution;
 
 type MonkeyId = usize;
 type WorryLevel = usize;
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 struct Item {
     worry_level: WorryLevel
 }
@@ -25,23 +28,23 @@ impl Display for Item {
 }
 
 impl Item {
-    pub fn inspect(&mut self, operation: &Operation, rollover: &WorryLevel) {
-        match operation {
-            Operation::Add(increment) => {
-                self.worry_level += increment;
-            },
-            Operation::Multiply(factor) => {
-                self.worry_level *= factor;
-            },
-            Operation::SquareOld => {
-                self.worry_level *= self.worry_level.clone();
-            }
-        }
-        self.worry_level %= rollover;
+    /**
+     * Work out the new worry level with twice the bits, so it can't overflow
+     * before being relieved and rolled over
+     */
+    pub fn inspect(&mut self, operation: &Operation, relief: &WorryLevel, rollover: &WorryLevel) {
+        let worry_level = self.worry_level as u128;
+        let worry_level = match operation {
+            Operation::Add(increment) => worry_level + *increment as u128,
+            Operation::Multiply(factor) => worry_level * *factor as u128,
+            Operation::SquareOld => worry_level * worry_level,
+        };
+        // Less than the rollover, so back within a worry level
+        self.worry_level = (worry_level / *relief as u128 % *rollover as u128) as WorryLevel;
     }
 
     pub fn divisibility_test(&self, test_value: &WorryLevel) -> bool {
-        &self.worry_level % test_value == 0
+        self.worry_level.is_multiple_of(*test_value)
     }
 }
 
@@ -51,14 +54,14 @@ struct MonkeyTurnResult {
     new_owner: MonkeyId
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 enum Operation {
     Add(WorryLevel),
     Multiply(WorryLevel),
     SquareOld
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 struct Monkey {
     id: MonkeyId,
     items: Vec<Item>,
@@ -69,37 +72,75 @@ struct Monkey {
     number_of_inspections: usize
 }
 
-impl<'a> From<Captures<'a>> for Monkey {
-    fn from(cap: Captures) -> Self {
-        Monkey {
-            id: cap[1].parse::<MonkeyId>().unwrap(),
-            items: cap[2].to_string()
-                         .split(", ")
-                         .map(|num| num.parse::<WorryLevel>().unwrap().into())
-                         .collect(),
-            operation: if &cap[4] == "old" {
-                Operation::SquareOld
-            } else if &cap[3] == "+" {
-                Operation::Add(cap[4].parse::<WorryLevel>().unwrap())
-            } else {
-                Operation::Multiply(cap[4].parse::<WorryLevel>().unwrap())
+/**
+ * What each line describing a monkey looks like
+ */
+const MONKEY_LINES: [(&str, &str); 6] = [
+    (r"^Monkey (\d+):$", "Monkey <id>:"),
+    (r"^  Starting items: ((?:[0-9]+(?:, [0-9]+)*)?)$", "  Starting items: <worry level>, ..."),
+    (r"^  Operation: new = old (\*|\+) (old|[0-9]+)$", "  Operation: new = old <+ or *> <old or number>"),
+    (r"^  Test: divisible by ([0-9]+)$", "  Test: divisible by <number>"),
+    (r"^    If true: throw to monkey (\d+)$", "    If true: throw to monkey <id>"),
+    (r"^    If false: throw to monkey (\d+)$", "    If false: throw to monkey <id>"),
+];
 
-            },
-            divisibility_test: cap[5].parse::<WorryLevel>().unwrap(),
-            on_true: cap[6].parse::<MonkeyId>().unwrap(),
-            on_false: cap[7].parse::<MonkeyId>().unwrap(),
-            number_of_inspections: 0
+impl Monkey {
+    /**
+     * Parse a monkey from the six lines describing it. `patterns` are the
+     * compiled regexes from `MONKEY_LINES`
+     */
+    fn from_lines(lines: &[Line], patterns: &[Regex]) -> Result<Self> {
+        let mut captures: Vec<Captures> = Vec::new();
+        for (index, line) in lines.iter().enumerate() {
+            let (_, expected) = MONKEY_LINES[index];
+            let cap = patterns[index]
+                .captures(line.text)
+                .ok_or_else(|| line.error(line.text, format!("expected `{}`, found", expected)))?;
+            captures.push(cap);
+        }
+        let number = |line: usize, group: usize, what: &str| -> Result<usize> {
+            lines[line].parse(captures[line].get(group).unwrap().as_str(), what)
+        };
+
+        let items = captures[1].get(1).unwrap().as_str();
+        let items = if items.is_empty() {
+            Vec::new()
+        } else {
+            items.split(", ")
+                 .map(|num| lines[1].parse::<WorryLevel>(num, "a worry level").map(Item::from))
+                 .collect::<Result<Vec<Item>>>()?
+        };
+        let operation = if &captures[2][2] == "old" {
+            Operation::SquareOld
+        } else if &captures[2][1] == "+" {
+            Operation::Add(number(2, 2, "a worry level")?)
+        } else {
+            Operation::Multiply(number(2, 2, "a worry level")?)
+        };
+        let divisibility_test = number(3, 1, "a divisor")?;
+        if divisibility_test == 0 {
+            return Err(lines[3].error(captures[3].get(1).unwrap().as_str(), "expected a divisor greater than 0, found"));
         }
+
+        Ok(Monkey {
+            id: number(0, 1, "a monkey id")?,
+            items,
+            operation,
+            divisibility_test,
+            on_true: number(4, 1, "a monkey id")?,
+            on_false: number(5, 1, "a monkey id")?,
+            number_of_inspections: 0
+        })
     }
 }
 
 impl Display for Monkey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Monkey {}:", self.id)?;
-        if self.items.len() > 0 {
-            write!(f, " {}", self.items[0])?;
-            for index in 1..self.items.len() {
-                write!(f, ", {}", self.items[index])?;
+        if let Some((first, rest)) = self.items.split_first() {
+            write!(f, " {}", first)?;
+            for item in rest {
+                write!(f, ", {}", item)?;
             }
         }
         Ok(())
@@ -107,19 +148,19 @@ impl Display for Monkey {
 }
 
 impl Monkey {
-    pub fn take_a_turn(&mut self, rollover: &WorryLevel) -> Vec<MonkeyTurnResult> {
+    pub fn take_a_turn(&mut self, relief: &WorryLevel, rollover: &WorryLevel) -> Vec<MonkeyTurnResult> {
         let mut results = Vec::new();
         while let Some(mut item) = self.items.pop() {
-            item.inspect(&self.operation, rollover);
+            item.inspect(&self.operation, relief, rollover);
             self.number_of_inspections += 1;
             if item.divisibility_test(&self.divisibility_test) {
                 results.push(MonkeyTurnResult {
-                    item: item,
+                    item,
                     new_owner: self.on_true
                 });
             } else {
                 results.push(MonkeyTurnResult {
-                    item: item,
+                    item,
                     new_owner: self.on_false
                 });
             }
@@ -139,45 +180,104 @@ impl Monkey {
     }
 }
 
-fn parse_input_to_monkeys(input: &str) -> Vec<Monkey> {
-    let re = Regex::new(
-        r"Monkey (\d{1}):
-  Starting items: ([0-9]+(?:, [0-9]+)*)*
-  Operation: new = old (\*|\+) (old|[0-9]+)
-  Test: divisible by ([0-9]+)
-    If true: throw to monkey (\d{1})
-    If false: throw to monkey (\d{1})"
-            ).unwrap();
-    let captures = re.captures_iter(input);
-    captures.map(|cap: Captures| {
-        cap.into()
-    }).collect()
+fn parse_input_to_monkeys(input: &str) -> Result<Vec<Monkey>> {
+    let patterns: Vec<Regex> = MONKEY_LINES.iter().map(|(pattern, _)| Regex::new(pattern).unwrap()).collect();
+    let mut monkeys: Vec<Monkey> = Vec::new();
+    for block in blocks(Q11::DAY, input) {
+        if block.len() > MONKEY_LINES.len() {
+            let line = &block[MONKEY_LINES.len()];
+            return Err(line.error(line.text, "expected a blank line before the next monkey, found"));
+        }
+        if block.len() < MONKEY_LINES.len() {
+            let (_, expected) = MONKEY_LINES[block.len()];
+            return Err(block.last().unwrap().error_at_end(format!("expected the next line to be `{}`", expected)));
+        }
+        monkeys.push(Monkey::from_lines(&block, &patterns)?);
+    }
+    Ok(monkeys)
 }
 
+/**
+ * Check that the monkeys only throw to each other, are numbered in order and
+ * that their divisors can be combined into a rollover
+ */
+fn check_monkeys(input: &str, monkeys: &[Monkey]) -> Result<()> {
+    let mut worry_level_rollover: WorryLevel = 1;
+    for (index, (monkey, block)) in monkeys.iter().zip(blocks(Q11::DAY, input)).enumerate() {
+        let id_text = block[0].text.trim_start_matches("Monkey ").trim_end_matches(':');
+        if monkey.id != index {
+            return Err(block[0].error(id_text, format!("expected monkey {}, found", index)));
+        }
+        for (line, target) in [(&block[4], monkey.on_true), (&block[5], monkey.on_false)] {
+            let target_text = line.text.rsplit(' ').next().unwrap();
+            if target >= monkeys.len() || target == monkey.id {
+                return Err(line.error(target_text, "expected the id of another monkey, found"));
+            }
+        }
+        worry_level_rollover = worry_level_rollover.checked_mul(monkey.divisibility_test).ok_or_else(|| {
+            block[3].error(block[3].text.trim(), "the product of every monkey's divisor is too large after")
+        })?;
+    }
 
-struct MonkeyManager {
+    if monkeys.len() < 2 {
+        let line = Line { day: Q11::DAY, number: 1, text: input.lines().next().unwrap_or_default() };
+        return Err(line.error_at_end("expected at least two monkeys"));
+    }
+    Ok(())
+}
+
+#[derive(Clone)]
+pub struct MonkeyManager {
     monkeys: Vec<Monkey>,
+    worry_level_relief: WorryLevel,
     worry_level_rollover: WorryLevel
 }
 
-impl From<&str> for MonkeyManager {
-    fn from(input: &str) -> Self {
-        let monkeys = parse_input_to_monkeys(input);
+impl TryFrom<&str> for MonkeyManager {
+    type Error = Error;
+
+    fn try_from(input: &str) -> Result<Self> {
+        let monkeys = parse_input_to_monkeys(input)?;
+        check_monkeys(input, &monkeys)?;
         let worry_level_rollover = monkeys.iter().map(|monkey: &Monkey| monkey.get_divisibility_test()).product();
-        Self {
+        Ok(Self {
             monkeys,
+            worry_level_relief: 1,
             worry_level_rollover,
+        })
+    }
+}
+
+impl Display for MonkeyManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for monkey in self.monkeys.iter() {
+            writeln!(f, "{}", monkey)?;
         }
+        Ok(())
     }
 }
 
 impl MonkeyManager {
+    /**
+     * Divide every worry level by `relief` after it is inspected.
+     *
+     * Division doesn't commute with the rollover modulus, so worry levels are
+     * left to grow when relief is applied.
+     */
+    pub fn with_relief(mut self, relief: WorryLevel) -> Self {
+        self.worry_level_relief = relief;
+        if relief != 1 {
+            self.worry_level_rollover = WorryLevel::MAX;
+        }
+        self
+    }
+
     pub fn play_round(&mut self) {
         for monkey_id in 0..self.monkeys.len() {
             let monkey_results;
             {
                 let main_monkey = &mut self.monkeys[monkey_id];
-                monkey_results = main_monkey.take_a_turn(&self.worry_level_rollover);
+                monkey_results = main_monkey.take_a_turn(&self.worry_level_relief, &self.worry_level_rollover);
             }
             for monkey_result in monkey_results {
                 self.monkeys[monkey_result.new_owner].give_item(monkey_result.item);
@@ -185,39 +285,135 @@ impl MonkeyManager {
         }
     }
 
-    pub fn print_monkeys(&self) {
-        for monkey in self.monkeys.iter() {
-            println!("{}", monkey);
-        }
+    /**
+     * The number of items each monkey has inspected, indexed by monkey id
+     */
+    pub fn inspections(&self) -> Vec<usize> {
+        self.monkeys.iter().map(|monkey| monkey.get_monkey_business()).collect()
     }
 
-    pub fn print_inspections(&self) {
-        for monkey in self.monkeys.iter() {
-            println!("Monkey 0 inspected items {} times.", monkey.get_monkey_business());
+    pub fn calculate_monkey_business(&self) -> usize {
+        monkey_business(self.inspections())
+    }
+
+    /**
+     * Follow one item, held by `monkey`, through a round, noting each monkey
+     * that inspects it. An item thrown to a monkey later in the round gets
+     * inspected again before the round is over
+     */
+    fn item_round(&self, (mut monkey, worry_level): (MonkeyId, WorryLevel), inspected_by: &mut Vec<MonkeyId>) -> (MonkeyId, WorryLevel) {
+        let mut item = Item::from(worry_level);
+        loop {
+            let thrower = &self.monkeys[monkey];
+            item.inspect(&thrower.operation, &self.worry_level_relief, &self.worry_level_rollover);
+            inspected_by.push(monkey);
+            let new_owner = if item.divisibility_test(&thrower.divisibility_test) { thrower.on_true } else { thrower.on_false };
+            let again = new_owner > monkey;
+            monkey = new_owner;
+            if !again {
+                return (monkey, item.worry_level);
+            }
         }
     }
 
-    pub fn calculate_monkey_business(&self) -> usize {
-        let mut inspections: Vec<usize> = self.monkeys.iter().map(|monkey| monkey.get_monkey_business()).collect();
-        inspections.sort();
-        inspections[inspections.len()-1]*inspections[inspections.len()-2]
+    /**
+     * The inspections each monkey will have made after another `rounds` rounds,
+     * without playing them.
+     *
+     * Without relief items never affect each other, and each one's monkey and
+     * rolled over worry level must eventually repeat, so every item's
+     * inspections can be extrapolated from its cycle. With relief worry levels
+     * aren't rolled over, so the rounds are played out instead
+     */
+    pub fn inspections_after(&self, rounds: u64) -> Vec<usize> {
+        if self.worry_level_relief != 1 {
+            let mut mm = self.clone();
+            for _ in 0..rounds {
+                mm.play_round();
+            }
+            return mm.inspections();
+        }
+
+        let mut inspections = self.inspections();
+        for monkey in &self.monkeys {
+            for item in &monkey.items {
+                let initial = (monkey.id, item.worry_level);
+                let step = |&state: &(MonkeyId, WorryLevel)| self.item_round(state, &mut Vec::new());
+                // A huge rollover can make for a cycle longer than the rounds
+                // asked for, when it's quicker to follow the item round by round
+                let Some(history) = find_cycle_within(initial, rounds.saturating_add(1), step) else {
+                    let mut inspected_by = Vec::new();
+                    (0..rounds).fold(initial, |state, _| self.item_round(state, &mut inspected_by));
+                    for id in inspected_by {
+                        inspections[id] += 1;
+                    }
+                    continue;
+                };
+                for (&state, visits) in history.states().iter().zip(history.visits(rounds)) {
+                    let mut inspected_by = Vec::new();
+                    self.item_round(state, &mut inspected_by);
+                    for id in inspected_by {
+                        inspections[id] += visits as usize;
+                    }
+                }
+            }
+        }
+        inspections
     }
 }
 
-pub fn solve_q11() {
-    let input = read_file("./puzzle_11.input");
-    let mut mm = MonkeyManager::from(&input as &str);
-    // for _ in 0..20 { // uncomment for part 1
-    for _ in 0..10000 { // part 2
-        mm.play_round();
+/**
+ * The product of the two largest numbers of inspections
+ */
+fn monkey_business(mut inspections: Vec<usize>) -> usize {
+    inspections.sort_unstable();
+    inspections[inspections.len()-1].saturating_mul(inspections[inspections.len()-2])
+}
+
+fn monkey_business_after(monkey_manager: &MonkeyManager, rounds: u64) -> usize {
+    monkey_business(monkey_manager.inspections_after(rounds))
+}
+
+pub struct Q11;
+
+impl Solution for Q11 {
+    const DAY: u8 = 11;
+    type Input = MonkeyManager;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        MonkeyManager::try_from(input)
+    }
+
+    fn part1(mm: &Self::Input) -> usize {
+        monkey_business_after(&mm.clone().with_relief(3), 20)
+    }
+
+    fn part2(mm: &Self::Input) -> usize {
+        monkey_business_after(mm, 10000)
     }
-    println!("solution1: {}", mm.calculate_monkey_business());
 }
 
+/**
+ * The level of monkey business after 20 rounds with relief
+ */
+pub fn part1(input: &str) -> Result<usize> {
+    Ok(Q11::part1(&Q11::parse(input)?))
+}
+
+/**
+ * The level of monkey business after 10000 rounds without relief
+ */
+pub fn part2(input: &str) -> Result<usize> {
+    Ok(Q11::part2(&Q11::parse(input)?))
+}
+
+#[cfg(test)]
 mod test {
-    use crate::q11::{parse_input_to_monkeys, WorryLevel};
+    use crate::q11::parse_input_to_monkeys;
 
-    use super::{Monkey, MonkeyManager};
+    use super::{monkey_business, Monkey, MonkeyManager};
 
     #[test]
     fn test_parse_monkey_0() {
@@ -238,49 +434,77 @@ Monkey 0:
             number_of_inspections: 0
         };
 
-        assert_eq!(expected_output, parse_input_to_monkeys(input)[0]);
+        assert_eq!(expected_output, parse_input_to_monkeys(input).unwrap()[0]);
     }
 
     #[test]
-    fn test_monkey_business() {
+    fn test_parse_errors() {
         let input = "
 Monkey 0:
   Starting items: 79, 98
-  Operation: new = old * 19
+  Operation: new = old ^ 19
   Test: divisible by 23
     If true: throw to monkey 2
-    If false: throw to monkey 3
+    If false: throw to monkey 3";
+        let error = parse_input_to_monkeys(input).unwrap_err().to_string();
+        assert!(error.starts_with("day 11 input, line 4, column 1: expected `  Operation"), "{}", error);
+
+        let input = "
+Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23";
+        let error = parse_input_to_monkeys(input).unwrap_err().to_string();
+        assert!(error.starts_with("day 11 input, line 5, column 24: expected the next line"), "{}", error);
+
+        let input = "
+Monkey 0:
+  Starting items: 79
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 1
+    If false: throw to monkey 0
 
 Monkey 1:
-  Starting items: 54, 65, 75, 74
+  Starting items: 54
   Operation: new = old + 6
   Test: divisible by 19
-    If true: throw to monkey 2
-    If false: throw to monkey 0
+    If true: throw to monkey 0
+    If false: throw to monkey 0";
+        let error = MonkeyManager::try_from(input).err().unwrap().to_string();
+        assert!(error.starts_with("day 11 input, line 7, column 31: expected the id of another monkey"), "{}", error);
+    }
 
-Monkey 2:
-  Starting items: 79, 60, 97
-  Operation: new = old * old
-  Test: divisible by 13
-    If true: throw to monkey 1
-    If false: throw to monkey 3
+    #[test]
+    fn test_monkey_business() {
+        let input = include_str!("../fixtures/day11/example.input");
 
-Monkey 3:
-  Starting items: 74
-  Operation: new = old + 3
-  Test: divisible by 17
-    If true: throw to monkey 0
-    If false: throw to monkey 1";
-
-    let mut mm = MonkeyManager::from(input);
-    for round in 0..10000 {
-        mm.play_round();
-        if round+1 % 1000 == 0 {
-            println!("=== After round {} ===", round+1);
-            mm.print_inspections();
+        let mut relieved_mm = MonkeyManager::try_from(input).unwrap().with_relief(3);
+        for _ in 0..20 {
+            relieved_mm.play_round();
+        }
+        assert_eq!(10605, relieved_mm.calculate_monkey_business());
+
+        let mut mm = MonkeyManager::try_from(input).unwrap();
+        for _ in 0..10000 {
+            mm.play_round();
         }
+        assert_eq!(2713310158, mm.calculate_monkey_business());
     }
-    mm.print_monkeys();
-    assert_eq!(2713310158, mm.calculate_monkey_business());
+
+    #[test]
+    fn test_inspections_after_matches_playing_rounds() {
+        let input = include_str!("../fixtures/day11/example.input");
+        let mm = MonkeyManager::try_from(input).unwrap();
+        let mut played = mm.clone();
+        for round in 0..=1000 {
+            if [0, 1, 20, 1000].contains(&round) {
+                assert_eq!(mm.inspections_after(round), played.inspections(), "after {} rounds", round);
+            }
+            played.play_round();
+        }
+
+        let relieved = mm.with_relief(3);
+        assert_eq!(monkey_business(relieved.inspections_after(20)), 10605);
     }
 }
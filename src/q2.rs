@@ -1,115 +1,397 @@
 
-use crate::common::read_file;
+use std::collections::{BTreeMap, BTreeSet};
 
-#[derive(Debug, PartialEq, Clone)]
-enum Action {
-    Rock,
-    Paper,
-    Scissors,
+use serde::Deserialize;
+
+use crate::common::{numbered_lines, Line};
+use crate::error::Result;
+use crate::solution::Solution;
+
+pub mod tournament;
+
+/**
+ * A move, by its place in the game's cycle of moves
+ */
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Action(pub usize);
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Deserialize)]
+pub enum Outcome {
+    Win,
+    Loss,
+    Draw,
 }
 
-impl Action {
-    pub fn to_score(&self) -> u64 {
-        match self {
-            Self::Rock => 1,
-            Self::Paper => 2,
-            Self::Scissors => 3
+/**
+ * The points for how a round ends, on top of the points for the move played
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutcomeScores {
+    pub win: u64,
+    pub draw: u64,
+    pub loss: u64,
+}
+
+impl Default for OutcomeScores {
+    fn default() -> Self {
+        Self { win: 6, draw: 3, loss: 0 }
+    }
+}
+
+/**
+ * A game as it's written down, naming moves rather than numbering them
+ */
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GameSpec {
+    moves: Vec<String>,
+    move_scores: Option<Vec<u64>>,
+    #[serde(default)]
+    outcome_scores: OutcomeScores,
+    #[serde(default)]
+    opponent: BTreeMap<String, String>,
+    #[serde(default)]
+    mine: BTreeMap<String, String>,
+    #[serde(default)]
+    outcomes: BTreeMap<String, Outcome>,
+}
+
+/**
+ * The rules of a cyclic game like rock paper scissors. The moves are listed
+ * so each beats the half of the others just before it in the cycle, wrapping
+ * around, and loses to the half just after it. The symbols say what each
+ * column of a strategy guide means: the opponent's moves, and mine either as
+ * moves or as the outcome I'm after
+ */
+#[derive(Debug, Clone)]
+pub struct Game {
+    moves: Vec<String>,
+    move_scores: Vec<u64>,
+    outcome_scores: OutcomeScores,
+    opponent: BTreeMap<String, Action>,
+    mine: BTreeMap<String, Action>,
+    outcomes: BTreeMap<String, Outcome>,
+}
+
+impl Game {
+    /**
+     * The puzzle's game, with A, B and C for the opponent's rock, paper and
+     * scissors, and X, Y and Z for mine or for losing, drawing and winning
+     */
+    pub fn rock_paper_scissors() -> Self {
+        Self::from_spec(GameSpec {
+            moves: names(&["Rock", "Paper", "Scissors"]),
+            move_scores: None,
+            outcome_scores: OutcomeScores::default(),
+            opponent: symbols(&[("A", "Rock"), ("B", "Paper"), ("C", "Scissors")]),
+            mine: symbols(&[("X", "Rock"), ("Y", "Paper"), ("Z", "Scissors")]),
+            outcomes: symbols(&[("X", Outcome::Loss), ("Y", Outcome::Draw), ("Z", Outcome::Win)]),
+        }).expect("rock paper scissors is a valid game")
+    }
+
+    /**
+     * Rock paper scissors lizard Spock, with A to E for the opponent's moves
+     * and V to Z for mine, each in the order rock, paper, scissors, lizard,
+     * Spock. X, Y and Z still mean losing, drawing and winning
+     */
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        let order = ["Rock", "Paper", "Scissors", "Lizard", "Spock"];
+        let pair = |symbols: &'static str| -> BTreeMap<String, String> {
+            symbols.chars().zip(order).map(|(symbol, name)| (symbol.to_string(), name.to_string())).collect()
+        };
+        Self::from_spec(GameSpec {
+            moves: names(&["Rock", "Spock", "Paper", "Lizard", "Scissors"]),
+            move_scores: Some(vec![1, 5, 2, 4, 3]),
+            outcome_scores: OutcomeScores::default(),
+            opponent: pair("ABCDE"),
+            mine: pair("VWXYZ"),
+            outcomes: symbols(&[("X", Outcome::Loss), ("Y", Outcome::Draw), ("Z", Outcome::Win)]),
+        }).expect("rock paper scissors lizard Spock is a valid game")
+    }
+
+    /**
+     * Read a game from TOML. Only `moves` is required; each move scores its
+     * place in the list unless `move_scores` says otherwise, and the outcome
+     * scores default to the puzzle's
+     *
+     * ```toml
+     * moves = ["Rock", "Paper", "Scissors"]
+     * move_scores = [1, 2, 3]
+     * outcome_scores = { win = 6, draw = 3, loss = 0 }
+     * opponent = { A = "Rock", B = "Paper", C = "Scissors" }
+     * mine = { X = "Rock", Y = "Paper", Z = "Scissors" }
+     * outcomes = { X = "Loss", Y = "Draw", Z = "Win" }
+     * ```
+     */
+    pub fn parse(s: &str) -> std::result::Result<Self, String> {
+        let spec: GameSpec = toml::from_str(s).map_err(|why| why.message().to_string())?;
+        Self::from_spec(spec)
+    }
+
+    fn from_spec(spec: GameSpec) -> std::result::Result<Self, String> {
+        let count = spec.moves.len();
+        if count < 3 || count.is_multiple_of(2) {
+            return Err(format!("a cyclic game needs an odd number of moves, at least 3, found {}", count));
         }
+        let mut seen = BTreeSet::new();
+        if let Some(name) = spec.moves.iter().find(|&name| !seen.insert(name)) {
+            return Err(format!("`{}` is listed more than once", name));
+        }
+        let move_scores = spec.move_scores.unwrap_or_else(|| (1..=count as u64).collect());
+        if move_scores.len() != count {
+            return Err(format!("expected a score for each of the {} moves, found {}", count, move_scores.len()));
+        }
+        let resolve = |symbols: BTreeMap<String, String>| {
+            symbols.into_iter()
+                .map(|(symbol, name)| match spec.moves.iter().position(|known| *known == name) {
+                    Some(index) => Ok((symbol, Action(index))),
+                    None => Err(format!("`{}` stands for `{}`, which isn't one of the moves", symbol, name)),
+                })
+                .collect::<std::result::Result<BTreeMap<_, _>, String>>()
+        };
+        let opponent = resolve(spec.opponent)?;
+        let mine = resolve(spec.mine)?;
+        Ok(Self {
+            moves: spec.moves,
+            move_scores,
+            outcome_scores: spec.outcome_scores,
+            opponent,
+            mine,
+            outcomes: spec.outcomes,
+        })
     }
 
-    pub fn from_str(s: &str) -> Self {
-        match s {
-            "A" | "X" => Self::Rock,
-            "B" | "Y" => Self::Paper,
-            "C" | "Z" => Self::Scissors,
-            _ => panic!("Unexpected value: ({})", s),
+    /**
+     * Every move, in the order of the cycle
+     */
+    pub fn moves(&self) -> impl Iterator<Item = Action> {
+        (0..self.moves.len()).map(Action)
+    }
+
+    pub fn name(&self, action: Action) -> &str {
+        &self.moves[action.0]
+    }
+
+    pub fn move_score(&self, action: Action) -> u64 {
+        self.move_scores[action.0]
+    }
+
+    pub fn outcome_score(&self, outcome: Outcome) -> u64 {
+        match outcome {
+            Outcome::Win => self.outcome_scores.win,
+            Outcome::Draw => self.outcome_scores.draw,
+            Outcome::Loss => self.outcome_scores.loss,
         }
     }
 
-    fn move_that_beats(&self) -> Action {
-        match self {
-            Action::Paper => Action::Scissors,
-            Action::Rock => Action::Paper,
-            Action::Scissors => Action::Rock
+    /**
+     * How the round ends for me playing `mine` against `theirs`
+     */
+    pub fn outcome(&self, mine: Action, theirs: Action) -> Outcome {
+        let count = self.moves.len();
+        match (mine.0 + count - theirs.0) % count {
+            0 => Outcome::Draw,
+            ahead if ahead <= count / 2 => Outcome::Win,
+            _ => Outcome::Loss,
         }
     }
 
-    fn move_that_draws(&self) -> Action {
-        self.clone()
+    /**
+     * A move that gets `outcome` against `theirs`. With more than three moves
+     * several might, and this is the one next to theirs in the cycle
+     */
+    pub fn response(&self, theirs: Action, outcome: Outcome) -> Action {
+        let count = self.moves.len();
+        match outcome {
+            Outcome::Draw => theirs,
+            Outcome::Win => Action((theirs.0 + 1) % count),
+            Outcome::Loss => Action((theirs.0 + count - 1) % count),
+        }
     }
 
-    fn move_that_losses(&self) -> Action {
-        self.move_that_beats().move_that_beats()
+    /**
+     * Read the strategy guide with my column as the moves to play
+     */
+    pub fn parse_moves(&self, input: &str) -> Result<Vec<Round>> {
+        numbered_lines(Q2::DAY, input)
+            .map(|line| {
+                let (op_column, my_column) = parse_columns(&line)?;
+                let op_action = lookup(&self.opponent, op_column, &line)?;
+                let my_action = lookup(&self.mine, my_column, &line)?;
+                Ok(Round { my_action, op_action })
+            })
+            .collect()
     }
 
-    pub fn from_input(op_action: &Action, result: &Outcome) -> Action {
-        match result {
-            Outcome::Draw => op_action.move_that_draws(),
-            Outcome::Win => op_action.move_that_beats(),
-            Outcome::Loss => op_action.move_that_losses()
+    /**
+     * Read the strategy guide with my column as the outcome to aim for
+     */
+    pub fn parse_outcomes(&self, input: &str) -> Result<Vec<Round>> {
+        numbered_lines(Q2::DAY, input)
+            .map(|line| {
+                let (op_column, result_column) = parse_columns(&line)?;
+                let op_action = lookup(&self.opponent, op_column, &line)?;
+                let result = lookup(&self.outcomes, result_column, &line)?;
+                Ok(Round { my_action: self.response(op_action, result), op_action })
+            })
+            .collect()
+    }
+
+    pub fn total_score(&self, rounds: &[Round]) -> u64 {
+        rounds.iter().map(|round| round.to_score(self)).sum()
+    }
+
+    /**
+     * Score the strategy guide under every way my column could be read: each
+     * assignment of the symbols it uses to distinct moves, then to distinct
+     * outcomes. Readings that need more moves or outcomes than there are
+     * aren't tried
+     */
+    pub fn analyze(&self, input: &str) -> Result<Analysis> {
+        let mut guide = Vec::new();
+        for line in numbered_lines(Q2::DAY, input) {
+            let (op_column, my_column) = parse_columns(&line)?;
+            guide.push((lookup(&self.opponent, op_column, &line)?, my_column));
         }
+        let symbols: Vec<&str> = guide.iter().map(|&(_, symbol)| symbol).collect::<BTreeSet<_>>().into_iter().collect();
+
+        let moves: Vec<Action> = self.moves().collect();
+        let as_moves = arrangements(&moves, symbols.len()).into_iter()
+            .map(|meanings| Interpretation::Moves(assign(&symbols, meanings)));
+        let as_outcomes = arrangements(&[Outcome::Loss, Outcome::Draw, Outcome::Win], symbols.len()).into_iter()
+            .map(|meanings| Interpretation::Outcomes(assign(&symbols, meanings)));
+
+        let readings = as_moves.chain(as_outcomes)
+            .map(|interpretation| {
+                let rounds: Vec<Round> = guide.iter()
+                    .map(|&(op_action, symbol)| Round { my_action: interpretation.my_action(self, op_action, symbol), op_action })
+                    .collect();
+                let scores: Vec<u64> = rounds.iter().map(|round| round.to_score(self)).collect();
+                Reading { total: scores.iter().sum(), interpretation, rounds, scores }
+            })
+            .collect();
+        Ok(Analysis { readings })
     }
 }
 
-enum Outcome {
-    Win,
-    Loss,
-    Draw,
+fn assign<T>(symbols: &[&str], meanings: Vec<T>) -> BTreeMap<String, T> {
+    symbols.iter().map(|symbol| symbol.to_string()).zip(meanings).collect()
 }
 
-impl Outcome {
-    pub fn to_score(&self) -> u64 {
-        match self {
-            Self::Win => 6,
-            Self::Draw => 3,
-            Self::Loss => 0,
+/**
+ * Every ordered choice of `k` distinct items
+ */
+fn arrangements<T: Copy>(items: &[T], k: usize) -> Vec<Vec<T>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+    let mut found = Vec::new();
+    for (index, &first) in items.iter().enumerate() {
+        let rest: Vec<T> = items.iter().enumerate().filter(|&(other, _)| other != index).map(|(_, &item)| item).collect();
+        for mut tail in arrangements(&rest, k - 1) {
+            tail.insert(0, first);
+            found.push(tail);
         }
     }
+    found
+}
 
-    pub fn from_str(s: &str) -> Self {
-        match s {
-            "X" => Self::Loss,
-            "Y" => Self::Draw,
-            "Z" => Self::Win,
-            _ => panic!("Unexpected input: {}", s)
+/**
+ * One way to read my column of a strategy guide
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Interpretation {
+    /// Each symbol is a move to play
+    Moves(BTreeMap<String, Action>),
+    /// Each symbol is an outcome to aim for
+    Outcomes(BTreeMap<String, Outcome>),
+}
+
+impl Interpretation {
+    fn my_action(&self, game: &Game, op_action: Action, symbol: &str) -> Action {
+        match self {
+            Self::Moves(moves) => moves[symbol],
+            Self::Outcomes(outcomes) => game.response(op_action, outcomes[symbol]),
         }
     }
 
-    pub fn from_round(round: &Round) -> Self {
-        Self::from_actions(&round.my_action, &round.op_action)
-    }
-
-    pub fn from_actions(my_action: &Action, op_action: &Action) -> Self {
-        match my_action {
-            Action::Rock => {
-                match op_action {
-                    Action::Rock => Outcome::Draw,
-                    Action::Paper => Outcome::Loss,
-                    Action::Scissors => Outcome::Win,
-                }
-            },
-            Action::Paper => {
-                match op_action {
-                    Action::Paper => Outcome::Draw,
-                    Action::Rock => Outcome::Win,
-                    Action::Scissors => Outcome::Loss
-                }
-            },
-            Action::Scissors => {
-                match op_action {
-                    Action::Scissors => Outcome::Draw,
-                    Action::Paper => Outcome::Win,
-                    Action::Rock => Outcome::Loss
-                }
-            }
-        }
+    /**
+     * The symbols and what they stand for, like `X = Rock, Y = Paper`
+     */
+    pub fn describe(&self, game: &Game) -> String {
+        let meanings: Vec<String> = match self {
+            Self::Moves(moves) => moves.iter().map(|(symbol, &action)| format!("{} = {}", symbol, game.name(action))).collect(),
+            Self::Outcomes(outcomes) => outcomes.iter().map(|(symbol, outcome)| format!("{} = {:?}", symbol, outcome)).collect(),
+        };
+        meanings.join(", ")
     }
 }
 
-#[derive(Debug)]
-struct Round {
-    my_action: Action,
-    op_action: Action,
+/**
+ * The strategy guide played under one interpretation
+ */
+#[derive(Debug, Clone)]
+pub struct Reading {
+    pub interpretation: Interpretation,
+    /// The rounds as they'd be played, in the guide's order
+    pub rounds: Vec<Round>,
+    /// The score of each round
+    pub scores: Vec<u64>,
+    pub total: u64,
+}
+
+/**
+ * Every reading of a strategy guide, moves first and then outcomes
+ */
+#[derive(Debug, Clone)]
+pub struct Analysis {
+    pub readings: Vec<Reading>,
+}
+
+impl Analysis {
+    /**
+     * The highest scoring reading, the first listed on a tie
+     */
+    pub fn best(&self) -> Option<&Reading> {
+        self.readings.iter().rev().max_by_key(|reading| reading.total)
+    }
+
+    /**
+     * The lowest scoring reading, the first listed on a tie
+     */
+    pub fn worst(&self) -> Option<&Reading> {
+        self.readings.iter().min_by_key(|reading| reading.total)
+    }
+}
+
+fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+fn symbols<T: Copy, U: From<T>>(pairs: &[(&str, T)]) -> BTreeMap<String, U> {
+    pairs.iter().map(|&(symbol, meaning)| (symbol.to_string(), meaning.into())).collect()
+}
+
+/**
+ * What `symbol` stands for, or an error listing the symbols that would do
+ */
+fn lookup<T: Copy>(symbols: &BTreeMap<String, T>, symbol: &str, line: &Line) -> Result<T> {
+    symbols.get(symbol).copied().ok_or_else(|| {
+        let known: Vec<&str> = symbols.keys().map(String::as_str).collect();
+        let expected = match known.split_last() {
+            None => "nothing".to_string(),
+            Some((only, [])) => only.to_string(),
+            Some((last, rest)) => format!("one of {} or {}", rest.join(", "), last),
+        };
+        line.error(symbol, format!("expected {}, found", expected))
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Round {
+    pub my_action: Action,
+    pub op_action: Action,
 }
 
 impl PartialEq for Round {
@@ -119,70 +401,63 @@ impl PartialEq for Round {
 }
 
 impl Round {
-    pub fn to_score(&self) -> u64 {
-        let outcome = Outcome::from_round(self);
-        self.my_action.to_score() + outcome.to_score()
+    pub fn to_score(&self, game: &Game) -> u64 {
+        let outcome = game.outcome(self.my_action, self.op_action);
+        game.move_score(self.my_action) + game.outcome_score(outcome)
     }
 }
 
-pub fn run1() {
-    let input = read_file("puzzle_2.input");
-    let rounds = parse_input(&input);
-    let score: u64 = rounds.into_iter().map(|round| { round.to_score()}).sum();
+pub struct Q2;
 
-    println!("The score from this input is: {}", score);
-}
+impl Solution for Q2 {
+    const DAY: u8 = 2;
+    /// The strategy guide read as (moves, outcomes)
+    type Input = (Vec<Round>, Vec<Round>);
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let game = Game::rock_paper_scissors();
+        Ok((game.parse_moves(input)?, game.parse_outcomes(input)?))
+    }
+
+    fn part1(input: &Self::Input) -> u64 {
+        Game::rock_paper_scissors().total_score(&input.0)
+    }
 
-pub fn run2() {
-    let input = read_file("puzzle_2.input");
-    let rounds = parse_input_modified(&input);
-    let score: u64 = rounds.into_iter().map(|round| { round.to_score()}).sum();
+    fn part2(input: &Self::Input) -> u64 {
+        Game::rock_paper_scissors().total_score(&input.1)
+    }
+}
 
-    println!("The score from this input using the modified strategy is: {}", score);
+/**
+ * The score from following the guide as a list of moves
+ */
+pub fn part1(input: &str) -> Result<u64> {
+    Ok(Q2::part1(&Q2::parse(input)?))
 }
 
-fn parse_input(s: &str) -> Vec<Round> {
-    s.split('\n')
-     .map(|s| {
-        s.trim()
-     })
-     .filter(|&s| {
-        s.len() == 3
-     })
-     .map(|subs| {
-        let mut round_string_it = subs.split(' ');
-        let op_action = Action::from_str(round_string_it.next().unwrap());
-        let my_action = Action::from_str(round_string_it.next().unwrap());
-        Round {
-            my_action,
-            op_action
-        }
-    } ).collect()
+/**
+ * The score from following the guide as a list of outcomes
+ */
+pub fn part2(input: &str) -> Result<u64> {
+    Ok(Q2::part2(&Q2::parse(input)?))
 }
 
-fn parse_input_modified(s: &str) -> Vec<Round> {
-    s.split('\n')
-    .map(|s| {
-       s.trim()
-    })
-    .filter(|&s| {
-       s.len() == 3
-    })
-    .map(|subs| {
-       let mut round_string_it = subs.split(' ');
-       let op_action = Action::from_str(round_string_it.next().unwrap());
-       let result = Outcome::from_str(round_string_it.next().unwrap());
-       let my_action = Action::from_input(&op_action, &result);
-       Round {
-           my_action,
-           op_action
-       }
-   } ).collect()
+/**
+ * Split a line of the strategy guide into the opponent's column and mine
+ */
+fn parse_columns<'a>(line: &Line<'a>) -> Result<(&'a str, &'a str)> {
+    line.text.trim()
+        .split_once(' ')
+        .ok_or_else(|| line.error(line.text.trim(), "expected two columns separated by a space, found"))
 }
+
+#[cfg(test)]
 mod test {
-    use crate::q2::{Round, Action};
+    use super::{part1, part2, Action, Game, Interpretation, Outcome, Round};
 
-    use super::parse_input;
+    const EXAMPLE: &str = include_str!("../fixtures/day2/example.input");
 
     #[test]
     fn test_input_parser() {
@@ -193,10 +468,102 @@ C Z
 
 ";
         let output = vec![
-            Round { my_action: Action::Rock, op_action: Action::Rock },
-            Round { my_action: Action::Paper, op_action: Action::Paper },
-            Round { my_action: Action::Scissors, op_action: Action::Scissors },
+            Round { my_action: Action(0), op_action: Action(0) },
+            Round { my_action: Action(1), op_action: Action(1) },
+            Round { my_action: Action(2), op_action: Action(2) },
         ];
-        assert_eq!(parse_input(input), output);
+        assert_eq!(Game::rock_paper_scissors().parse_moves(input).unwrap(), output);
+    }
+
+    #[test]
+    fn test_input_parser_error() {
+        let input = "A X
+B W
+";
+        let game = Game::rock_paper_scissors();
+        let error = game.parse_outcomes(input).unwrap_err().to_string();
+        assert!(error.starts_with("day 2 input, line 2, column 3: expected one of X, Y or Z, found `W`"), "{}", error);
+        let error = game.parse_moves("D X").unwrap_err().to_string();
+        assert!(error.starts_with("day 2 input, line 1, column 1: expected one of A, B or C, found `D`"), "{}", error);
+    }
+
+    #[test]
+    fn test_example() {
+        assert_eq!(part1(EXAMPLE).unwrap(), 15);
+        assert_eq!(part2(EXAMPLE).unwrap(), 12);
+    }
+
+    #[test]
+    fn test_cyclic_outcomes() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let by_name = |name: &str| game.moves().find(|&action| game.name(action) == name).unwrap();
+        let beats = |a: &str, b: &str| game.outcome(by_name(a), by_name(b)) == Outcome::Win;
+        assert!(beats("Scissors", "Paper") && beats("Paper", "Rock") && beats("Rock", "Lizard"));
+        assert!(beats("Lizard", "Spock") && beats("Spock", "Scissors") && beats("Scissors", "Lizard"));
+        assert!(beats("Lizard", "Paper") && beats("Paper", "Spock") && beats("Spock", "Rock"));
+        assert!(beats("Rock", "Scissors"));
+
+        // Every move beats half the others and loses to the other half, and
+        // the response always gets the outcome asked for
+        for mine in game.moves() {
+            let wins = game.moves().filter(|&theirs| game.outcome(mine, theirs) == Outcome::Win).count();
+            let losses = game.moves().filter(|&theirs| game.outcome(mine, theirs) == Outcome::Loss).count();
+            assert_eq!((wins, losses), (2, 2));
+            for outcome in [Outcome::Win, Outcome::Draw, Outcome::Loss] {
+                assert_eq!(game.outcome(game.response(mine, outcome), mine), outcome);
+            }
+        }
+
+        let rounds = game.parse_moves("A V\nE W\nB X").unwrap();
+        assert_eq!(rounds.iter().map(|round| round.to_score(&game)).collect::<Vec<_>>(), vec![4, 8, 9]);
+    }
+
+    #[test]
+    fn test_game_from_toml() {
+        let game = Game::parse(r#"
+            moves = ["Rock", "Paper", "Scissors"]
+            move_scores = [10, 20, 30]
+            outcome_scores = { win = 2, draw = 1, loss = 0 }
+            opponent = { R = "Rock", P = "Paper", S = "Scissors" }
+            mine = { r = "Rock", p = "Paper", s = "Scissors" }
+            outcomes = { l = "Loss", d = "Draw", w = "Win" }
+        "#).unwrap();
+        assert_eq!(game.total_score(&game.parse_moves("R p\nS p").unwrap()), 22 + 20);
+        assert_eq!(game.total_score(&game.parse_outcomes("R w\nS l").unwrap()), 22 + 20);
+
+        let error = |toml: &str| Game::parse(toml).unwrap_err();
+        assert_eq!(error(r#"moves = ["Rock", "Paper"]"#), "a cyclic game needs an odd number of moves, at least 3, found 2");
+        assert_eq!(error(r#"moves = ["Rock", "Rock", "Paper"]"#), "`Rock` is listed more than once");
+        assert_eq!(error(r#"moves = ["Rock", "Paper", "Scissors"]
+                            move_scores = [1]"#), "expected a score for each of the 3 moves, found 1");
+        assert_eq!(error(r#"moves = ["Rock", "Paper", "Scissors"]
+                            mine = { X = "Well" }"#), "`X` stands for `Well`, which isn't one of the moves");
+    }
+
+    #[test]
+    fn test_analyze() {
+        let game = Game::rock_paper_scissors();
+        let analysis = game.analyze(EXAMPLE).unwrap();
+        assert_eq!(analysis.readings.len(), 12);
+
+        let total = |wanted: &Interpretation| analysis.readings.iter().find(|reading| reading.interpretation == *wanted).unwrap().total;
+        let as_moves = Interpretation::Moves([("X", 0), ("Y", 1), ("Z", 2)].iter().map(|&(s, m)| (s.to_string(), Action(m))).collect());
+        let as_outcomes = Interpretation::Outcomes([("X", Outcome::Loss), ("Y", Outcome::Draw), ("Z", Outcome::Win)]
+            .iter().map(|&(s, o)| (s.to_string(), o)).collect());
+        assert_eq!(total(&as_moves), 15);
+        assert_eq!(total(&as_outcomes), 12);
+        assert_eq!(as_moves.describe(&game), "X = Rock, Y = Paper, Z = Scissors");
+
+        // Playing the move that beats each of A, B and C wins every round
+        let best = analysis.best().unwrap();
+        assert_eq!((best.total, best.interpretation.describe(&game).as_str()), (24, "X = Scissors, Y = Paper, Z = Rock"));
+        assert_eq!(best.scores, vec![8, 9, 7]);
+        let worst = analysis.worst().unwrap();
+        assert_eq!((worst.total, worst.interpretation.describe(&game).as_str()), (6, "X = Rock, Y = Scissors, Z = Paper"));
+        assert!(analysis.readings.iter().all(|reading| reading.scores.iter().sum::<u64>() == reading.total));
+
+        let error = game.analyze("A X
+D Y").unwrap_err().to_string();
+        assert!(error.starts_with("day 2 input, line 2, column 1: expected one of A, B or C, found `D`"), "{}", error);
     }
 }
@@ -0,0 +1,295 @@
+use std::{cmp::{Ordering, Reverse}, fmt::Display};
+
+use crate::generate::Rng;
+
+use super::{Action, Game, Outcome, Round};
+
+/**
+ * Someone to play the game against, choosing a move each round and hearing
+ * what was played once the round is over
+ */
+pub trait Opponent {
+    fn play(&mut self, game: &Game) -> Action;
+
+    /**
+     * Learn how the last round went: `mine` is what this opponent played and
+     * `theirs` what it was up against
+     */
+    fn observe(&mut self, _mine: Action, _theirs: Action) {}
+}
+
+/**
+ * Plays the moves it's given in order, starting over when it runs out
+ */
+#[derive(Debug, Clone)]
+pub struct Script {
+    moves: Vec<Action>,
+    next: usize,
+}
+
+impl Script {
+    /**
+     * A script of `moves`, which mustn't be empty
+     */
+    pub fn new(moves: Vec<Action>) -> Self {
+        assert!(!moves.is_empty(), "a script needs at least one move");
+        Self { moves, next: 0 }
+    }
+}
+
+impl Opponent for Script {
+    fn play(&mut self, _game: &Game) -> Action {
+        let action = self.moves[self.next];
+        self.next = (self.next + 1) % self.moves.len();
+        action
+    }
+}
+
+/**
+ * Picks any move with the same chance, the same way every time for a seed
+ */
+#[derive(Debug, Clone)]
+pub struct Random {
+    rng: Rng,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Self { rng: Rng::new(seed) }
+    }
+}
+
+impl Opponent for Random {
+    fn play(&mut self, game: &Game) -> Action {
+        Action(self.rng.index(game.moves().count()))
+    }
+}
+
+/**
+ * The move played most so far, or `None` before anything has been. The
+ * earliest move in the cycle wins a tie
+ */
+fn most_common(counts: &[u64]) -> Option<Action> {
+    counts.iter()
+        .enumerate()
+        .filter(|&(_, &count)| count > 0)
+        .rev()
+        .max_by_key(|&(_, &count)| count)
+        .map(|(index, _)| Action(index))
+}
+
+/**
+ * Plays whatever beats the move its opponent has played most often
+ */
+#[derive(Debug, Clone, Default)]
+pub struct Frequency {
+    counts: Vec<u64>,
+}
+
+impl Opponent for Frequency {
+    fn play(&mut self, game: &Game) -> Action {
+        match most_common(&self.counts) {
+            Some(expected) => game.response(expected, Outcome::Win),
+            None => Action(0),
+        }
+    }
+
+    fn observe(&mut self, _mine: Action, theirs: Action) {
+        if self.counts.len() <= theirs.0 {
+            self.counts.resize(theirs.0 + 1, 0);
+        }
+        self.counts[theirs.0] += 1;
+    }
+}
+
+/**
+ * Counts which move its opponent plays after each of their moves, and plays
+ * whatever beats the likeliest follow up to their last one. Falls back on
+ * their most common move when the last one hasn't been followed yet
+ */
+#[derive(Debug, Clone, Default)]
+pub struct Markov {
+    /// How often each move followed each move, indexed `[before][after]`
+    transitions: Vec<Vec<u64>>,
+    counts: Vec<u64>,
+    last: Option<Action>,
+}
+
+impl Markov {
+    /**
+     * Make room to count at least `count` moves
+     */
+    fn fit(&mut self, count: usize) {
+        let count = count.max(self.counts.len());
+        self.counts.resize(count, 0);
+        self.transitions.resize(count, Vec::new());
+        for row in &mut self.transitions {
+            row.resize(count, 0);
+        }
+    }
+}
+
+impl Opponent for Markov {
+    fn play(&mut self, game: &Game) -> Action {
+        self.fit(game.moves().count());
+        let expected = self.last
+            .and_then(|last| most_common(&self.transitions[last.0]))
+            .or_else(|| most_common(&self.counts));
+        match expected {
+            Some(expected) => game.response(expected, Outcome::Win),
+            None => Action(0),
+        }
+    }
+
+    fn observe(&mut self, _mine: Action, theirs: Action) {
+        self.fit(theirs.0 + 1);
+        if let Some(last) = self.last {
+            self.transitions[last.0][theirs.0] += 1;
+        }
+        self.counts[theirs.0] += 1;
+        self.last = Some(theirs);
+    }
+}
+
+/**
+ * How an entrant did over the whole tournament. Matches are won by scoring
+ * more over their rounds than the other side
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Standing {
+    pub name: String,
+    pub score: u64,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+/**
+ * The standings, highest score first
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leaderboard {
+    pub standings: Vec<Standing>,
+}
+
+impl Display for Leaderboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self.standings.iter().map(|standing| standing.name.len()).max().unwrap_or(0);
+        for (place, standing) in self.standings.iter().enumerate() {
+            writeln!(f, "{:>2}. {:<width$}  {:>8}  {}W {}D {}L", place + 1, standing.name, standing.score,
+                standing.wins, standing.draws, standing.losses, width = width)?;
+        }
+        Ok(())
+    }
+}
+
+/**
+ * Makes a fresh opponent for each match, so nothing learned carries over
+ */
+type Factory = Box<dyn Fn() -> Box<dyn Opponent>>;
+
+/**
+ * A round robin where every entrant plays every other for a set number of
+ * rounds, each round scored like a line of the strategy guide
+ */
+pub struct Tournament {
+    game: Game,
+    rounds: usize,
+    entrants: Vec<(String, Factory)>,
+}
+
+impl Tournament {
+    pub fn new(game: Game, rounds: usize) -> Self {
+        Self { game, rounds, entrants: Vec::new() }
+    }
+
+    pub fn enter<O: Opponent + 'static>(mut self, name: impl Into<String>, make: impl Fn() -> O + 'static) -> Self {
+        self.entrants.push((name.into(), Box::new(move || Box::new(make()))));
+        self
+    }
+
+    /**
+     * Play one match, returning each side's score
+     */
+    pub fn play_match(&self, first: &mut dyn Opponent, second: &mut dyn Opponent) -> (u64, u64) {
+        let mut scores = (0, 0);
+        for _ in 0..self.rounds {
+            let (a, b) = (first.play(&self.game), second.play(&self.game));
+            scores.0 += Round { my_action: a, op_action: b }.to_score(&self.game);
+            scores.1 += Round { my_action: b, op_action: a }.to_score(&self.game);
+            first.observe(a, b);
+            second.observe(b, a);
+        }
+        scores
+    }
+
+    pub fn run(&self) -> Leaderboard {
+        let mut standings: Vec<Standing> = self.entrants.iter()
+            .map(|(name, _)| Standing { name: name.clone(), score: 0, wins: 0, draws: 0, losses: 0 })
+            .collect();
+        for first in 0..self.entrants.len() {
+            for second in first + 1..self.entrants.len() {
+                let (a, b) = self.play_match(&mut *(self.entrants[first].1)(), &mut *(self.entrants[second].1)());
+                standings[first].score += a;
+                standings[second].score += b;
+                match a.cmp(&b) {
+                    Ordering::Greater => { standings[first].wins += 1; standings[second].losses += 1; }
+                    Ordering::Less => { standings[first].losses += 1; standings[second].wins += 1; }
+                    Ordering::Equal => { standings[first].draws += 1; standings[second].draws += 1; }
+                }
+            }
+        }
+        standings.sort_by_key(|standing| Reverse(standing.score));
+        Leaderboard { standings }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Frequency, Markov, Opponent, Random, Script, Tournament};
+    use crate::q2::{Action, Game};
+
+    #[test]
+    fn test_opponents() {
+        let game = Game::rock_paper_scissors();
+        let mut script = Script::new(vec![Action(0), Action(2)]);
+        let played: Vec<Action> = (0..5).map(|_| script.play(&game)).collect();
+        assert_eq!(played, vec![Action(0), Action(2), Action(0), Action(2), Action(0)]);
+
+        let moves = |seed| { let mut random = Random::new(seed); (0..20).map(|_| random.play(&game)).collect::<Vec<_>>() };
+        assert_eq!(moves(7), moves(7));
+        assert!(moves(7).iter().all(|action| action.0 < 3));
+
+        // Once it has seen mostly rock it plays paper
+        let mut frequency = Frequency::default();
+        for theirs in [0, 0, 2] {
+            frequency.observe(Action(1), Action(theirs));
+        }
+        assert_eq!(frequency.play(&game), Action(1));
+    }
+
+    #[test]
+    fn test_tournament() {
+        let tournament = Tournament::new(Game::rock_paper_scissors(), 300)
+            .enter("rock", || Script::new(vec![Action(0)]))
+            .enter("cycle", || Script::new(vec![Action(0), Action(1), Action(2)]))
+            .enter("frequency", Frequency::default)
+            .enter("markov", Markov::default)
+            .enter("random", || Random::new(2022));
+
+        // The Markov player learns the cycle and the rock, and beats both
+        let mut markov = Markov::default();
+        let (cycle, learner) = tournament.play_match(&mut Script::new(vec![Action(0), Action(1), Action(2)]), &mut markov);
+        assert!(learner > cycle + 1500, "{} against {}", learner, cycle);
+
+        let leaderboard = tournament.run();
+        assert_eq!(leaderboard.standings.len(), 5);
+        assert_eq!(leaderboard.standings[0].name, "markov");
+        assert!(leaderboard.standings.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        for standing in &leaderboard.standings {
+            assert_eq!(standing.wins + standing.draws + standing.losses, 4);
+        }
+        assert_eq!(leaderboard, tournament.run());
+        assert!(leaderboard.to_string().starts_with(" 1. markov"), "{}", leaderboard);
+    }
+}
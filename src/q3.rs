@@ -1,6 +1,8 @@
 use std::collections::{HashMap, hash_map::RandomState};
 
-use crate::common::{read_file, parse_lines};
+use crate::common::{numbered_lines, Line};
+use crate::error::Result;
+use crate::solution::Solution;
 
 
 fn parse_line(s: &str) -> (&str, &str) {
@@ -9,19 +11,37 @@ fn parse_line(s: &str) -> (&str, &str) {
 
 const ASCII_A: u64 = 65;
 const ASCII_Z: u64 = 90;
-const ASCII_a: u64 = 97;
-const ASCII_z: u64 = 172;
+const ASCII_LOWER_A: u64 = 97;
+const ASCII_LOWER_Z: u64 = 122;
 const UPPER_CASE_OFFSET: u64 = ASCII_A - 27;
-const LOWER_CASE_OFFSET: u64 = ASCII_a - 1;
+const LOWER_CASE_OFFSET: u64 = ASCII_LOWER_A - 1;
 
+/**
+ * a-z are worth 1-26 and A-Z 27-52. Anything else, like the `'\0'` for a group
+ * with no badge, is worth nothing
+ */
 fn get_priority(c: char) -> u64 {
-    let ascii: u64 = c.try_into().unwrap();
-    if ascii >= ASCII_A && ascii <= ASCII_Z {
+    let ascii: u64 = c.into();
+    if (ASCII_A..=ASCII_Z).contains(&ascii) {
         ascii - UPPER_CASE_OFFSET
-    } else {
+    } else if (ASCII_LOWER_A..=ASCII_LOWER_Z).contains(&ascii) {
         ascii - LOWER_CASE_OFFSET
+    } else {
+        0
     }
+}
 
+/**
+ * Check that a rucksack holds an even number of items, each a letter
+ */
+fn parse_rucksack(line: &Line) -> Result<String> {
+    if let Some((index, c)) = line.text.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+        return Err(line.error(&line.text[index..index + c.len_utf8()], "expected an item between a-z or A-Z, found"));
+    }
+    if !line.text.len().is_multiple_of(2) {
+        return Err(line.error(line.text, "expected an even number of items, found"));
+    }
+    Ok(line.text.to_string())
 }
 
 fn get_common_chars(first: &str, second: &str) -> Vec<char> {
@@ -55,30 +75,65 @@ fn get_badge(first: &str, second: &str, third: &str) -> char{
     '\0'
 }
 
-pub fn run() {
-    let input = "./puzzle_3.input";
-    let contents = read_file(input);
-    let lines = parse_lines(&contents);
+pub struct Q3;
+
+impl Solution for Q3 {
+    const DAY: u8 = 3;
+    type Input = Vec<String>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        numbered_lines(Q3::DAY, input).map(|line| parse_rucksack(&line)).collect()
+    }
+
+    fn part1(lines: &Self::Input) -> u64 {
+        sum_common_item_priorities(lines)
+    }
+
+    fn part2(lines: &Self::Input) -> u64 {
+        sum_badge_priorities(lines)
+    }
+}
+
+fn sum_common_item_priorities<S: AsRef<str>>(lines: &[S]) -> u64 {
     let mut sum = 0;
     for line in lines {
-        let (first, second) = parse_line(line);
-        sum += get_priority(*get_common_chars(first, second).first().unwrap());
+        let (first, second) = parse_line(line.as_ref());
+        sum += get_common_chars(first, second).first().map_or(0, |&c| get_priority(c));
     }
+    sum
+}
 
-    println!("The sum of priorities for the common items is {}", sum);
-    sum = 0;
-    let lines = parse_lines(&contents);
-
+fn sum_badge_priorities<S: AsRef<str>>(lines: &[S]) -> u64 {
+    let mut sum = 0;
     for set in lines.chunks(3) {
         if set.len() == 3 {
-            sum += get_priority(get_badge(set[0], set[1], set[2]))
+            sum += get_priority(get_badge(set[0].as_ref(), set[1].as_ref(), set[2].as_ref()))
         }
     }
-    println!("The sum of priorities for the badges is {}", sum);
+    sum
+}
+
+/**
+ * The sum of priorities for the items common to both compartments
+ */
+pub fn part1(input: &str) -> Result<u64> {
+    Ok(Q3::part1(&Q3::parse(input)?))
 }
 
+/**
+ * The sum of priorities for the badges of each group of three
+ */
+pub fn part2(input: &str) -> Result<u64> {
+    Ok(Q3::part2(&Q3::parse(input)?))
+}
+
+#[cfg(test)]
 mod test {
-    use super::{parse_line, parse_lines, get_priority, get_common_chars};
+    use crate::common::numbered_lines;
+
+    use super::{parse_line, parse_rucksack, get_priority, get_common_chars};
 
     #[test]
     fn test_parse_line() {
@@ -90,6 +145,14 @@ mod test {
         assert_eq!(second, expected_second);
     }
 
+    #[test]
+    fn test_parse_rucksack() {
+        let mut lines = numbered_lines(3, "vJrwpWtwJgWrhcsFMMfFFhFp\nvJrw pW\nabc");
+        assert_eq!(parse_rucksack(&lines.next().unwrap()).unwrap(), "vJrwpWtwJgWrhcsFMMfFFhFp");
+        assert!(parse_rucksack(&lines.next().unwrap()).is_err());
+        assert!(parse_rucksack(&lines.next().unwrap()).is_err());
+    }
+
     #[test]
     fn test_get_priority() {
         let inputs = vec!['a', 'b', 'c', 'z', 'A', 'B', 'C', 'Y', 'Z'];
@@ -98,6 +161,8 @@ mod test {
             get_priority(c)
         }).collect();
         assert_eq!(expected_outputs, outputs);
+        assert_eq!(get_priority('\0'), 0);
+        assert_eq!(get_priority('['), 0);
     }
 
     #[test]
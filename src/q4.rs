@@ -1,43 +1,43 @@
-use crate::common::{read_file, parse_lines};
+use crate::common::{interval::Interval, numbered_lines, Line, parse::{self, Parser, literal, unsigned}};
+use crate::error::Result;
+use crate::solution::Solution;
 
-#[derive(Copy, Clone, PartialEq, Debug)]
-struct Region {
-    lower: u32,
-    upper: u32
-}
+/// A range of section numbers assigned to an elf
+pub type Region = Interval;
 
-impl Region {
-    pub fn contains(&self, other: &Self) -> bool {
-        self.lower <= other.lower && self.upper >= other.upper
-    }
+pub struct Q4;
+
+impl Solution for Q4 {
+    const DAY: u8 = 4;
+    type Input = Vec<(Region, Region)>;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-    pub fn overlaps(&self, other: &Self) -> bool {
-        self.lower <= other.upper && self.lower >= other.lower
+    fn parse(input: &str) -> Result<Self::Input> {
+        numbered_lines(Q4::DAY, input).map(|line| parse_line(&line)).collect()
     }
-}
 
-pub fn run() {
-    let input = "./puzzle_4.input";
-    let contents = read_file(input);
-    let lines = parse_lines(&contents);
-    let mut sum = 0;
-    for line in lines {
-        let (r1, r2) = parse_line(line);
-        if contains(&r1, &r2) {
-            sum += 1;
-        }
+    fn part1(pairs: &Self::Input) -> usize {
+        pairs.iter().filter(|(r1, r2)| contains(r1, r2)).count()
     }
-    println!("{} pairs have one range fully containing the other", sum);
-
-    let lines = parse_lines(&contents);
-    let mut sum = 0;
-    for line in lines {
-        let (r1, r2) = parse_line(line);
-        if overlaps(&r1, &r2) {
-            sum += 1;
-        }
+
+    fn part2(pairs: &Self::Input) -> usize {
+        pairs.iter().filter(|(r1, r2)| overlaps(r1, r2)).count()
     }
-    println!("{} pairs overlap", sum);
+}
+
+/**
+ * The number of pairs where one range fully contains the other
+ */
+pub fn part1(input: &str) -> Result<usize> {
+    Ok(Q4::part1(&Q4::parse(input)?))
+}
+
+/**
+ * The number of pairs that overlap
+ */
+pub fn part2(input: &str) -> Result<usize> {
+    Ok(Q4::part2(&Q4::parse(input)?))
 }
 
 fn contains(r1: &Region, r2: &Region) -> bool {
@@ -45,38 +45,33 @@ fn contains(r1: &Region, r2: &Region) -> bool {
 }
 
 fn overlaps(r1: &Region, r2: &Region) -> bool {
-    r1.overlaps(r2) || r2.overlaps(r1)
+    r1.overlaps(r2)
 }
 
-fn parse_line(line: &str) -> (Region, Region) {
-    let regions: Vec<Region> = line.split(',').map(|range| {
-        let range: Vec<&str> = range.split('-').collect();
-        Region {
-            lower: range[0].parse().unwrap(),
-            upper: range[1].parse().unwrap()
-        }
-    }).collect();
+fn region<'a>() -> impl Parser<'a, Region> {
+    unsigned("a section number")
+        .left(literal("-"))
+        .then(unsigned("a section number"))
+        .try_map(|(lower, upper)| {
+            Region::new(lower, upper).ok_or_else(|| "expected the range to start before it ends, found".to_string())
+        })
+}
 
-    (regions[0], regions[1])
+fn parse_line(line: &Line) -> Result<(Region, Region)> {
+    parse::parse_line(line, region().left(literal(",")).then(region()))
 }
 
+#[cfg(test)]
 mod test {
+    use crate::common::Line;
+
     use super::{Region, contains, parse_line};
 
     #[test]
     fn test_contains() {
-        let r1 = Region {
-            lower: 4,
-            upper: 6
-        };
-        let r2 = Region {
-            lower: 6,
-            upper: 6,
-        };
-        let r3 = Region {
-            lower: 5,
-            upper: 8
-        };
+        let r1 = Region::new(4, 6).unwrap();
+        let r2 = Region::new(6, 6).unwrap();
+        let r3 = Region::new(5, 8).unwrap();
 
         assert!(contains(&r1, &r2));
         assert!(!contains(&r1, &r3));
@@ -87,15 +82,13 @@ mod test {
     #[test]
     fn test_parse_line() {
         let input = "3-5,7-10";
-        let expected_output = (
-            Region {
-                lower: 3, upper: 5
-            },
-            Region {
-                lower: 7,
-                upper: 10
-            }
-        );
-        assert_eq!(parse_line(input), expected_output);
+        let expected_output = (Region::new(3, 5).unwrap(), Region::new(7, 10).unwrap());
+        let line = Line { day: 4, number: 1, text: input };
+        assert_eq!(parse_line(&line).unwrap(), expected_output);
+
+        let line = Line { day: 4, number: 1, text: "3-5,7-x" };
+        assert!(parse_line(&line).is_err());
+        let line = Line { day: 4, number: 1, text: "3-5" };
+        assert!(parse_line(&line).is_err());
     }
 }
@@ -1,65 +1,152 @@
-use std::collections::HashMap;
+use std::fmt::Display;
 
-use crate::common::{parse_lines, read_file};
+use crate::animate::Frame;
+use crate::common::{blocks, Line, parse::{Parser, literal, parse_line, unsigned}};
+use crate::error::Result;
+use crate::solution::Solution;
 
-pub fn run() {
-    let input = "./puzzle_5.input";
-    let contents = read_file(input);
-
-    let (boxes, instructions) = contents.split_once("\n\n").unwrap();
+#[derive(Clone)]
+pub struct Procedure {
+    box_stacks: Vec<Vec<char>>,
+    instructions: Vec<Instruction>,
+}
 
-    let boxes = parse_lines(boxes);
-    let mut box_stacks: Vec<Vec<char>> = parse_boxes(boxes);
+pub struct Q5;
 
-    let instructions: Vec<Instruction> = parse_lines(instructions).into_iter().map(|line| {
-        Instruction::from_line(line)
-    }).collect();
+impl Solution for Q5 {
+    const DAY: u8 = 5;
+    type Input = Procedure;
+    type Answer1 = String;
+    type Answer2 = String;
 
-    for instruction in instructions {
-        for _ in 0..instruction.num_to_move {
-            let b = box_stacks.get_mut(instruction.from).unwrap().pop().unwrap();
-            box_stacks.get_mut(instruction.to).unwrap().push(b);
-        }
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_procedure(input)
     }
 
-    print!("The final box order is: ");
-    for stack in box_stacks {
-        print!("{}", stack.last().unwrap());
+    fn part1(procedure: &Self::Input) -> String {
+        top_of_stacks(&crate_mover_9000(procedure))
     }
-    println!();
 
+    fn part2(procedure: &Self::Input) -> String {
+        top_of_stacks(&crate_mover_9001(procedure))
+    }
 }
 
-pub fn run2() {
-    let input = "./puzzle_5.input";
-    let contents = read_file(input);
+fn parse_procedure(contents: &str) -> Result<Procedure> {
+    let mut blocks = blocks(Q5::DAY, contents);
+    let boxes = blocks.next().unwrap_or_default();
+    let box_stacks: Vec<Vec<char>> = parse_boxes(boxes.iter().map(|line| line.text).collect());
 
-    let (boxes, instructions) = contents.split_once("\n\n").unwrap();
+    let mut instructions = blocks.flatten().peekable();
+    if instructions.peek().is_none() {
+        let last_line = contents.lines().last().unwrap_or_default();
+        let line = Line { day: Q5::DAY, number: contents.lines().count().max(1), text: last_line };
+        return Err(line.error_at_end("expected a blank line followed by the instructions"));
+    }
+    let instructions: Vec<Instruction> = instructions
+        .map(|line| Instruction::from_line(&line, box_stacks.len()))
+        .collect::<Result<_>>()?;
 
-    let boxes = parse_lines(boxes);
-    let mut box_stacks: Vec<Vec<char>> = parse_boxes(boxes);
+    Ok(Procedure {
+        box_stacks,
+        instructions
+    })
+}
 
-    let instructions: Vec<Instruction> = parse_lines(instructions).into_iter().map(|line| {
-        Instruction::from_line(line)
-    }).collect();
+/**
+ * Move the boxes for one instruction, one at a time, as the CrateMover 9000 does
+ */
+fn crate_mover_9000_step(box_stacks: &mut [Vec<char>], instruction: &Instruction) {
+    for _ in 0..instruction.num_to_move {
+        // Moving more boxes than the stack holds stops once it's empty
+        let Some(b) = box_stacks[instruction.from].pop() else { break };
+        box_stacks[instruction.to].push(b);
+    }
+}
 
-    for instruction in instructions {
-        let mut stack: Vec<char> = Vec::new();
-        for _ in 0..instruction.num_to_move {
-            let b = box_stacks.get_mut(instruction.from).unwrap().pop().unwrap();
-            stack.push(b);
-        }
-        while stack.last().is_some() {
-            box_stacks.get_mut(instruction.to).unwrap().push(stack.pop().unwrap());
-        }
+/**
+ * Move the boxes for one instruction all at once, keeping their order, as the
+ * CrateMover 9001 does
+ */
+fn crate_mover_9001_step(box_stacks: &mut [Vec<char>], instruction: &Instruction) {
+    let mut stack: Vec<char> = Vec::new();
+    for _ in 0..instruction.num_to_move {
+        let Some(b) = box_stacks[instruction.from].pop() else { break };
+        stack.push(b);
+    }
+    while let Some(b) = stack.pop() {
+        box_stacks.get_mut(instruction.to).unwrap().push(b);
     }
+}
+
+fn crate_mover_9000(procedure: &Procedure) -> Vec<Vec<char>> {
+    let mut box_stacks = procedure.box_stacks.clone();
+    for instruction in procedure.instructions.iter() {
+        crate_mover_9000_step(&mut box_stacks, instruction);
+    }
+    box_stacks
+}
+
+fn crate_mover_9001(procedure: &Procedure) -> Vec<Vec<char>> {
+    let mut box_stacks = procedure.box_stacks.clone();
+    for instruction in procedure.instructions.iter() {
+        crate_mover_9001_step(&mut box_stacks, instruction);
+    }
+    box_stacks
+}
+
+fn top_of_stacks(box_stacks: &[Vec<char>]) -> String {
+    box_stacks.iter().filter_map(|stack| stack.last()).collect()
+}
 
-    print!("The final box order for the 9001 is: ");
-    for stack in box_stacks {
-        print!("{}", stack.last().unwrap());
+/**
+ * Draw the stacks the way the puzzle input does, with the stack numbers
+ * underneath
+ */
+fn draw_stacks(box_stacks: &[Vec<char>]) -> String {
+    let height = box_stacks.iter().map(Vec::len).max().unwrap_or(0);
+    let mut lines: Vec<String> = (0..height).rev()
+        .map(|level| {
+            box_stacks.iter()
+                .map(|stack| stack.get(level).map_or("   ".to_string(), |b| format!("[{}]", b)))
+                .collect::<Vec<String>>()
+                .join(" ")
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+    lines.push((1..=box_stacks.len()).map(|number| format!(" {} ", number)).collect::<Vec<String>>().join(" ").trim_end().to_string());
+    lines.join("\n")
+}
+
+/**
+ * The stacks before the crane starts and after each instruction, using the
+ * CrateMover 9001 if `keep_order` is set and the CrateMover 9000 otherwise
+ */
+pub fn crane_frames(input: &str, keep_order: bool) -> Result<Vec<Frame>> {
+    let procedure = Q5::parse(input)?;
+    let step = if keep_order { crate_mover_9001_step } else { crate_mover_9000_step };
+    let mut box_stacks = procedure.box_stacks.clone();
+    let mut frames = vec![Frame::new(draw_stacks(&box_stacks), "the starting stacks")];
+    for instruction in &procedure.instructions {
+        step(&mut box_stacks, instruction);
+        frames.push(Frame::new(draw_stacks(&box_stacks), instruction.to_string()));
     }
-    println!();
+    Ok(frames)
+}
 
+/**
+ * The boxes on top of each stack after the CrateMover 9000 is done
+ */
+pub fn part1(input: &str) -> Result<String> {
+    Ok(Q5::part1(&Q5::parse(input)?))
+}
+
+/**
+ * The boxes on top of each stack after the CrateMover 9001 is done
+ */
+pub fn part2(input: &str) -> Result<String> {
+    Ok(Q5::part2(&Q5::parse(input)?))
 }
 
 fn parse_boxes(mut boxes: Vec<&str>) -> Vec<Vec<char>> {
@@ -68,8 +155,8 @@ fn parse_boxes(mut boxes: Vec<&str>) -> Vec<Vec<char>> {
     for line in boxes {
         let chunks = line.as_bytes().chunks(4);
         for (stack_number, chunk) in chunks.enumerate() {
-            if chunk.len() > 2 && chunk[1].is_ascii_digit() { continue; }
-            if box_stacks.len() == 0 || ((box_stacks.len() - 1) < stack_number)  {
+            if chunk.len() < 2 || chunk[1].is_ascii_digit() { continue; }
+            while box_stacks.len() <= stack_number {
                 box_stacks.push(Vec::new());
             }
             box_stacks.get_mut(stack_number).unwrap().push(chunk[1] as char);
@@ -88,25 +175,50 @@ fn trim_top_of_stack(stack: &mut Vec<char>) {
     }
 }
 
+#[derive(Clone)]
 struct Instruction {
     pub num_to_move: u32,
     pub from: usize,
     pub to: usize
 }
 
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "move {} from {} to {}", self.num_to_move, self.from + 1, self.to + 1)
+    }
+}
+
 impl Instruction {
-    pub fn from_line(line: &str) -> Instruction {
-        let parts: Vec<&str> = line.split(' ').collect();
-        Instruction {
-            num_to_move: parts[1].parse().unwrap(),
-            from: parts[3].parse::<usize>().unwrap() - 1,
-            to: parts[5].parse::<usize>().unwrap() - 1
-        }
+    pub fn from_line(line: &Line, num_stacks: usize) -> Result<Instruction> {
+        let stack = || unsigned::<usize>("a stack number").try_map(move |stack| {
+            if stack == 0 || stack > num_stacks {
+                return Err(format!("expected a stack between 1 and {}, found", num_stacks));
+            }
+            Ok(stack - 1)
+        });
+        let instruction = literal("move ").right(unsigned("a number of boxes"))
+            .left(literal(" from ")).then(stack())
+            .left(literal(" to ")).then(stack())
+            .map(|((num_to_move, from), to)| Instruction { num_to_move, from, to });
+        parse_line(line, instruction)
     }
 }
 
+#[cfg(test)]
 mod test {
-    use super::parse_boxes;
+    use super::{crane_frames, draw_stacks, parse_boxes, part1, part2};
+
+    const EXAMPLE: &str = include_str!("../fixtures/day5/example.input");
+
+    #[test]
+    fn test_example() {
+        assert_eq!(part1(EXAMPLE).unwrap(), "CMZ");
+        assert_eq!(part2(EXAMPLE).unwrap(), "MCD");
+
+        // Without the padding that lines the rows up to the same width
+        let unpadded: String = EXAMPLE.lines().map(|line| format!("{}\n", line.trim_end())).collect();
+        assert_eq!(part1(&unpadded).unwrap(), "CMZ");
+    }
 
     #[test]
     fn test_parse_boxes() {
@@ -124,4 +236,26 @@ mod test {
 
         assert_eq!(parse_boxes(input.split('\n').collect()), expected_output);
     }
+
+    #[test]
+    fn test_draw_stacks() {
+        // Drawn stacks aren't padded out to the same width
+        let boxes = "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3";
+        assert_eq!(draw_stacks(&parse_boxes(boxes.lines().collect())), boxes);
+    }
+
+    #[test]
+    fn test_crane_frames() {
+        let frames = crane_frames(EXAMPLE, false).unwrap();
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[1].status, "move 1 from 2 to 1");
+        assert_eq!(frames[4].picture, "        [Z]
+        [N]
+        [D]
+[C] [M] [P]
+ 1   2   3");
+
+        let frames = crane_frames(EXAMPLE, true).unwrap();
+        assert!(frames.last().unwrap().picture.starts_with("        [D]"));
+    }
 }
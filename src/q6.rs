@@ -1,14 +1,51 @@
-use std::collections::{HashSet, hash_map::RandomState};
+use std::{collections::{HashSet, hash_map::RandomState}, path::Path};
 
-use crate::common::read_file;
+use crate::common::MappedInput;
+use crate::error::Result;
+use crate::solution::Solution;
 
-pub fn run() {
-    let input = "./puzzle_6.input";
-    let data_stream = read_file(input);
-    let result: usize = process_data_stream(&data_stream, 4);
-    println!("The packet starts at: {}", result);
-    let result: usize = process_data_stream(&data_stream, 14);
-    println!("The message starts at: {}", result);
+pub struct Q6;
+
+impl Solution for Q6 {
+    const DAY: u8 = 6;
+    type Input = String;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.trim().to_string())
+    }
+
+    fn part1(data_stream: &Self::Input) -> usize {
+        process_data_stream(data_stream, 4)
+    }
+
+    fn part2(data_stream: &Self::Input) -> usize {
+        process_data_stream(data_stream, 14)
+    }
+}
+
+/**
+ * The number of characters processed before the start-of-packet marker
+ */
+pub fn part1(input: &str) -> Result<usize> {
+    Ok(Q6::part1(&Q6::parse(input)?))
+}
+
+/**
+ * The number of characters processed before the start-of-message marker
+ */
+pub fn part2(input: &str) -> Result<usize> {
+    Ok(Q6::part2(&Q6::parse(input)?))
+}
+
+/**
+ * Find the first marker of `num_unique` distinct characters in a data stream
+ * file without reading it into memory, for streams too big to parse
+ */
+pub fn process_data_stream_file<P: AsRef<Path>>(path: P, num_unique: usize) -> Result<usize> {
+    let input = MappedInput::open(path)?;
+    Ok(process_data_stream(input.as_str()?.trim(), num_unique))
 }
 
 struct Buffer {
@@ -20,8 +57,7 @@ struct Buffer {
 
 impl Buffer {
     pub fn new(max_length: usize) -> Buffer {
-        let mut v = Vec::new();
-        v.resize(max_length, ' ');
+        let v = vec![' '; max_length];
         Buffer {
             len: 0,
             max_len: max_length,
@@ -41,6 +77,7 @@ impl Buffer {
         *self.vec.get_mut((self.head + self.len) % self.max_len).unwrap() = c;
     }
 
+    #[cfg(test)]
     pub unsafe fn get_vec(&mut self) -> &mut Vec<char> {
         &mut self.vec
     }
@@ -53,6 +90,10 @@ impl Buffer {
 
 }
 pub fn process_data_stream(input: &str, num_unique: usize) -> usize {
+    // A marker of no characters is there before reading any
+    if num_unique == 0 {
+        return 0;
+    }
     let mut buf = Buffer::new(num_unique);
     for (index, character) in input.chars().enumerate() {
         buf.push(character);
@@ -63,8 +104,11 @@ pub fn process_data_stream(input: &str, num_unique: usize) -> usize {
     0
 }
 
+#[cfg(test)]
 mod test {
-    use super::{Buffer, process_data_stream};
+    use std::{env, fs};
+
+    use super::{Buffer, process_data_stream, process_data_stream_file};
 
     #[test]
     fn test_push() {
@@ -111,4 +155,13 @@ mod test {
         assert_eq!(process_data_stream(input_3, 4), expected_output_3);
         assert_eq!(process_data_stream(input_4, 4), expected_output_4);
     }
+
+    #[test]
+    fn test_process_data_stream_file() {
+        let path = env::temp_dir().join(format!("advent-of-code-q6-{}", std::process::id()));
+        fs::write(&path, "nppdvjthqldpwncqszvftbrmjlhg\n").unwrap();
+        assert_eq!(process_data_stream_file(&path, 4).unwrap(), 6);
+        assert_eq!(process_data_stream_file(&path, 14).unwrap(), 23);
+        fs::remove_file(path).unwrap();
+    }
 }
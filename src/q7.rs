@@ -1,14 +1,16 @@
-use std::{io::{BufRead, Split}, fmt::Error, slice::Iter};
+use std::slice::Iter;
 
-use crate::common::{parse_lines, self};
+use crate::common::{self, numbered_lines, Line};
+use crate::error::Result;
+use crate::solution::Solution;
 
 #[derive(PartialEq, Debug)]
-struct FileSystem {
+pub struct FileSystem {
     pub root_directory: Directory
 }
 
 #[derive(PartialEq, Debug)]
-struct Directory {
+pub struct Directory {
     pub child_directories: Vec<Directory>,
     pub files: Vec<File>,
     pub name: Vec<u8>,
@@ -16,7 +18,7 @@ struct Directory {
 }
 
 #[derive(PartialEq, Debug)]
-struct File {
+pub struct File {
     pub size: u64
 }
 
@@ -28,6 +30,18 @@ impl FileSystem {
     }
 }
 
+impl Default for FileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for Directory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Directory {
     pub fn new() -> Self {
         Self {
@@ -51,23 +65,33 @@ pub enum Token {
     ListDirectoryLine(Vec<Vec<u8>>)
 }
 
-fn into_token_stream(lines: &Vec<&str>) -> Vec<Token> {
+/**
+ * Check that a line of the terminal session is a command we understand or a
+ * line of `ls` output, so the tokenizer can rely on its shape
+ */
+fn validate_line(line: &Line) -> Result<()> {
+    let tokens: Vec<&str> = line.text.split(' ').collect();
+    if let Some(empty) = tokens.iter().find(|token| token.is_empty()) {
+        return Err(line.error(empty, "expected tokens separated by a single space"));
+    }
+    match tokens.as_slice() {
+        ["$", "cd", _] | ["$", "ls"] | ["dir", _] => Ok(()),
+        ["$", command, ..] => Err(line.error(command, "expected `cd <directory>` or `ls`, found")),
+        [size, _] => line.parse::<u64>(size, "a file size or `dir`").map(|_| ()),
+        _ => Err(line.error(line.text, "expected a command or a directory listing, found")),
+    }
+}
+
+fn into_token_stream(lines: &[&str]) -> Vec<Token> {
     let mut tokens: Vec<Token> = Vec::new();
 
     for &line in lines {
         let line: &[u8] = line.as_bytes();
         let chunks = line.split(|&c| c == b' ');
-        if line[0] == b'$' {
-            for chunk in chunks {
-                if chunk[0] == b'c' && chunk[1] == b'd' && chunk.len() == 2 {
-                    continue;
-                }
-                if chunk[0] == b'l' && chunk[1] == b's' && chunk.len() == 2 {
-                    continue;
-                }
-                if chunk[0] != b'$' || chunk.len() > 1  {
-                    tokens.push(Token::DirectoryName(chunk.into()));
-                }
+        if line.first() == Some(&b'$') {
+            let command: Vec<&[u8]> = chunks.collect();
+            if let [b"$", b"cd", name] = command.as_slice() {
+                tokens.push(Token::DirectoryName(name.to_vec()));
             }
         }
         else {
@@ -84,11 +108,11 @@ fn into_token_stream(lines: &Vec<&str>) -> Vec<Token> {
 }
 
 impl FileSystem {
-    pub fn parse_tokens(tokens: &Vec<Token>) -> FileSystem {
+    pub fn parse_tokens(tokens: &[Token]) -> FileSystem {
         let mut fs = FileSystem::new();
-        let mut current_directory = &mut fs.root_directory;
-        let mut token_iter = tokens.into_iter();
-        while FileSystem::parse_directory(&mut current_directory, &mut token_iter) {}
+        let current_directory = &mut fs.root_directory;
+        let mut token_iter = tokens.iter();
+        while FileSystem::parse_directory(current_directory, &mut token_iter) {}
 
         fs
     }
@@ -121,17 +145,16 @@ impl FileSystem {
                         }
                     },
                     Token::ListDirectoryLine(line) => {
-                        if line.len() < 2 { panic!("Found List directory line with less then 2 entries")}
-                        let first = line[0].clone();
-                        let second = line[1].clone();
-
-                        let size = String::from_utf8(first).unwrap().parse::<u64>();
-                        if let Ok(size) = size {
+                        // Lines are checked before they're tokenized, so anything
+                        // else is a `dir <name>` line
+                        let [first, second] = line.as_slice() else { continue };
+                        let size = std::str::from_utf8(first).ok().and_then(|size| size.parse::<u64>().ok());
+                        if let Some(size) = size {
                             directory.files.push(File{
-                                size: size
+                                size
                             });
                         } else {
-                            directory.child_directories.push(Directory::with_name(second))
+                            directory.child_directories.push(Directory::with_name(second.clone()))
                         }
                     }
                 }
@@ -144,16 +167,17 @@ impl FileSystem {
 
 impl Directory {
     pub fn get_size(&self) -> u64 {
-        let sum1: u64 = self.files.iter().map(|file| file.size).sum();
-        let sum2: u64 = self.child_directories.iter().map(|dir| dir.get_size()).sum();
-        sum1 + sum2
+        // Saturate rather than overflow on sizes no real disk could hold
+        self.files.iter().map(|file| file.size)
+            .chain(self.child_directories.iter().map(|dir| dir.get_size()))
+            .fold(0, u64::saturating_add)
     }
 
     pub fn get_sum_less_than(&self, n: u64) -> u64 {
         let size = self.get_size();
-        let sum_less: u64 = self.child_directories.iter().map(|dir| dir.get_sum_less_than(n)).sum();
+        let sum_less: u64 = self.child_directories.iter().map(|dir| dir.get_sum_less_than(n)).fold(0, u64::saturating_add);
         if size < n {
-            sum_less + size
+            sum_less.saturating_add(size)
         } else {
             sum_less
         }
@@ -161,37 +185,66 @@ impl Directory {
 
     pub fn find_smallest_child_less_than(&self, n: u64) -> u64 {
         let smallest_child = self.child_directories.iter().map(|child| child.find_smallest_child_less_than(n)).filter(|&size| size > n).min();
-        if smallest_child.is_none() || smallest_child.unwrap() < n {
-            self.get_size()
-        } else {
-            smallest_child.unwrap()
+        match smallest_child {
+            Some(size) if size >= n => size,
+            _ => self.get_size()
         }
     }
 }
 
-pub fn solve_q7() {
-    let input_file_name = "./puzzle_7.input";
-    let file = common::read_file(input_file_name);
-    let lines = common::parse_lines(&file);
-
-    let tokens = into_token_stream(&lines);
-    let fs = FileSystem::parse_tokens(&tokens);
-
-    let root_dir = fs.root_directory;
-    println!("Sum of directories' sizes with individual size less than 100000 is: {}", root_dir.get_sum_less_than(100000));
-    let root_dir_size = root_dir.get_size();
-    let space_available = 70000000 - root_dir_size;
-    let space_required = 30000000 - space_available;
-    let answer2 = root_dir.find_smallest_child_less_than(space_required);
-    println!("Size of directory to yeet is: {}", answer2);
+pub struct Q7;
+
+impl Solution for Q7 {
+    const DAY: u8 = 7;
+    type Input = FileSystem;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        for line in numbered_lines(Q7::DAY, input) {
+            validate_line(&line)?;
+        }
+        let lines = common::parse_lines(input);
+        let tokens = into_token_stream(&lines);
+        Ok(FileSystem::parse_tokens(&tokens))
+    }
+
+    fn part1(fs: &Self::Input) -> u64 {
+        fs.root_directory.get_sum_less_than(100000)
+    }
+
+    fn part2(fs: &Self::Input) -> u64 {
+        let root_dir = &fs.root_directory;
+        let root_dir_size = root_dir.get_size();
+        let space_available = 70000000u64.saturating_sub(root_dir_size);
+        let space_required = 30000000u64.saturating_sub(space_available);
+        root_dir.find_smallest_child_less_than(space_required)
+    }
+}
+
+/**
+ * The sum of the sizes of every directory smaller than 100000
+ */
+pub fn part1(input: &str) -> Result<u64> {
+    Ok(Q7::part1(&Q7::parse(input)?))
+}
+
+/**
+ * The size of the smallest directory that frees up enough space for the update
+ */
+pub fn part2(input: &str) -> Result<u64> {
+    Ok(Q7::part2(&Q7::parse(input)?))
 }
 
 
+#[cfg(test)]
 mod test {
     use crate::q7::File;
 
-    use super::{Token, into_token_stream, FileSystem, Directory};
-    use std::{iter::zip, vec};
+    use crate::common::numbered_lines;
+
+    use super::{Token, into_token_stream, validate_line, FileSystem, Directory};
+    use std::iter::zip;
 
     #[test]
     fn test_into_token_stream() {
@@ -218,6 +271,22 @@ mod test {
         for cmp in zip(output, expected_output) {
             assert_eq!(cmp.0, cmp.1);
         }
+
+        // Directories named like the commands are still directories
+        let output = into_token_stream(&["$ cd c", "$ cd l", "$ ls", "$ cd cd", "$ cd ls"]);
+        assert_eq!(output, vec![
+            Token::DirectoryName(b"c".to_vec()),
+            Token::DirectoryName(b"l".to_vec()),
+            Token::DirectoryName(b"cd".to_vec()),
+            Token::DirectoryName(b"ls".to_vec()),
+        ]);
+    }
+
+    #[test]
+    fn test_validate_line() {
+        let input = "$ cd /\n$ ls\ndir a\n14848514 b.txt\n$ rm a\n12x b.txt\n$ cd\n$  ls";
+        let results: Vec<bool> = numbered_lines(7, input).map(|line| validate_line(&line).is_ok()).collect();
+        assert_eq!(results, vec![true, true, true, true, false, false, false, false]);
     }
 
     #[test]
@@ -1,183 +1,116 @@
-use std::fmt::Debug;
+use crate::common::grid::{Cell, Grid, ORTHOGONAL};
+use crate::error::Result;
+use crate::render::{heat, Image, Rgb};
+use crate::solution::Solution;
 
-use crate::common::{parse_lines, read_file};
+/// How trees that can't be seen from outside the grid are drawn
+const HIDDEN: Rgb = [40, 40, 40];
 
-trait PrintOut {
-    fn print(&self);
+fn parse_puzzle_input_to_tree_grid(input: &str) -> Result<Grid<u32>> {
+    Grid::parse(Q8::DAY, input, |c| c.to_digit(10), "a tree height between 0 and 9")
 }
 
-impl<T> PrintOut for  Vec<Vec<T>>
-where T : Debug {
-    fn print(&self) {
-        for row in self {
-            for item in row {
-                print!("{:?} ", item);
-            }
-            println!();
-        }
-    }
+fn is_visible(grid: &Grid<u32>, tree: Cell) -> bool {
+    ORTHOGONAL.iter().any(|&step| grid.ray(tree, step).all(|other| grid[other] < grid[tree]))
 }
 
-fn parse_puzzle_input_to_tree_grid(input: String) -> Vec<Vec<u32>> {
-    let lines = parse_lines(&input);
-    let mut tree_grid: Vec<Vec<u32>> = Vec::new();
-    for line in lines {
-        let line: &[u8] = line.as_bytes();
-        let mut tree_row = Vec::new();
-        for entry in line {
-            tree_row.push((entry - b'0') as u32);
-        }
-        tree_grid.push(tree_row);
-    }
-    tree_grid
+fn get_visible_trees(grid: &Grid<u32>) -> u32 {
+    grid.cells()
+        .filter(|&tree| is_visible(grid, tree))
+        .count() as u32
 }
 
-fn get_visible_trees(grid: &Vec<Vec<u32>>) -> u32 {
-    let mut grid_vis: Vec<Vec<bool>> = Vec::new();
-    for i in 0..grid.len() {
-        let mut grid_row = Vec::new();
-        for j in 0..grid[0].len() {
-            grid_row.push(i == 0 || i == (grid.len()-1) || j == 0 || j == (grid[0].len()-1));
-        }
-        grid_vis.push(grid_row);
-    }
-
-    /* Rows right to left */
-    for (row_index, row) in grid.iter().enumerate() {
-        let mut max_height = 0;
-        for (column_index, &tree_height) in row.iter().enumerate() {
-            if tree_height > max_height {
-                max_height = tree_height;
-                grid_vis[row_index][column_index] = true;
+fn get_scenic_score(grid: &Grid<u32>, tree_row: usize, tree_col: usize) -> u32 {
+    let tree = (tree_row, tree_col);
+    let tree_height = grid[tree];
+    ORTHOGONAL.iter()
+        .map(|&step| {
+            let mut counter = 0;
+            for other in grid.ray(tree, step) {
+                counter += 1;
+                if grid[other] >= tree_height {
+                    break; // View Blocked
+                }
             }
-        }
-    }
-
-    /* Rows left to right */
-    for (row_index, row) in grid.iter().enumerate() {
-        let mut max_height = 0;
-        for column_index in (0..row.len()).rev() {
-            let tree_height = row[column_index];
-            if tree_height > max_height {
-                max_height = tree_height;
-                grid_vis[row_index][column_index] = true;
-            }
-        }
-    }
-
-    for column_index in 0..grid[0].len() {
-        let mut max_height = 0;
-        for row_index in 0..grid.len() {
-            if grid[row_index][column_index] > max_height {
-                max_height = grid[row_index][column_index];
-                grid_vis[row_index][column_index] = true;
-            }
-        }
-    }
-
-    for column_index in 0..grid[0].len() {
-        let mut max_height = 0;
-        for row_index in (0..grid.len()).rev() {
-            if grid[row_index][column_index] > max_height {
-                max_height = grid[row_index][column_index];
-                grid_vis[row_index][column_index] = true;
-            }
-        }
-    }
+            counter
+        })
+        // Only a forest hundreds of trees across could overflow
+        .fold(1, u32::saturating_mul)
+}
 
-    grid_vis
-        .into_iter()
-        .map::<u32, _>(|row| row.into_iter().map(|b| (b as u32)).sum())
-        .sum()
+fn get_most_scenic_tree(grid: &Grid<u32>) -> u32 {
+    grid.cells()
+        .map(|(row, column)| get_scenic_score(grid, row, column))
+        .max()
+        .unwrap_or(0)
 }
 
-fn get_scenic_score(grid: &Vec<Vec<u32>>, tree_row: usize, tree_col: usize) -> u32 {
-    let tree_height = grid[tree_row][tree_col];
-    let mut score: u32 = 1;
-    let mut counter = 0;
-
-    for i in (0..tree_row).rev() {
-        let other_height = grid[i][tree_col];
-        counter += 1;
-        if other_height >= tree_height {
-            break; // View Blocked
-        }
-    }
-    score *= counter;
-    counter = 0;
-
-    for i in tree_row+1..grid.len() {
-        let other_height = grid[i][tree_col];
-        counter += 1;
-        if other_height >= tree_height {
-            break; // View Blocked
-        }
-    }
+/**
+ * A picture of the forest with a pixel per tree. Trees visible from outside are
+ * colored by scenic score, from dark blue for the lowest to yellow for the
+ * most scenic, and hidden trees are grey
+ */
+pub fn scenic_heatmap(input: &str) -> Result<Image> {
+    let grid = Q8::parse(input)?;
+    let best = get_most_scenic_tree(&grid) as u64;
+    let mut image = Image::new(grid.width(), grid.height(), HIDDEN);
+    for (row, column) in grid.cells().filter(|&tree| is_visible(&grid, tree)) {
+        image.set(column, row, heat(get_scenic_score(&grid, row, column) as u64, best));
+    }
+    Ok(image)
+}
 
-    score *= counter;
+pub struct Q8;
 
-    counter = 0;
+impl Solution for Q8 {
+    const DAY: u8 = 8;
+    type Input = Grid<u32>;
+    type Answer1 = u32;
+    type Answer2 = u32;
 
-    for i in tree_col+1..grid.len() {
-        let other_height = grid[tree_row][i];
-        counter += 1;
-        if other_height >= tree_height {
-            break; // View Blocked
-        }
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_puzzle_input_to_tree_grid(input)
     }
 
-    score *= counter;
-
-
-    counter = 0;
-
-    for i in (0..tree_col).rev() {
-        let other_height = grid[tree_row][i];
-        counter += 1;
-        if other_height >= tree_height {
-            break; // View Blocked
-        }
+    fn part1(grid: &Self::Input) -> u32 {
+        get_visible_trees(grid)
     }
 
-    score *= counter;
-
-    score
-}
-
-fn get_most_scenic_tree(grid: &Vec<Vec<u32>>) -> u32 {
-    let mut best_score = 0;
-    for row_index in 1..(grid.len() - 1) {
-        for col_index in 1..(grid[0].len() - 1) {
-            best_score = u32::max(best_score, get_scenic_score(grid, row_index, col_index));
-        }
+    fn part2(grid: &Self::Input) -> u32 {
+        get_most_scenic_tree(grid)
     }
-    best_score
 }
 
-pub fn solve_q8() {
-    let input_file_name = "puzzle_8.input";
-    let input_file = read_file(input_file_name);
-    let grid = parse_puzzle_input_to_tree_grid(input_file);
-    let solution1 = get_visible_trees(&grid);
-    let solution2 = get_most_scenic_tree(&grid);
+/**
+ * The number of trees visible from outside the grid
+ */
+pub fn part1(input: &str) -> Result<u32> {
+    Ok(Q8::part1(&Q8::parse(input)?))
+}
 
-    println!("Solution 1: {}", solution1);
-    println!("Solution 2: {}", solution2);
+/**
+ * The highest scenic score of any tree
+ */
+pub fn part2(input: &str) -> Result<u32> {
+    Ok(Q8::part2(&Q8::parse(input)?))
 }
 
+#[cfg(test)]
 mod test {
+    use crate::common::grid::Grid;
     use crate::q8::{parse_puzzle_input_to_tree_grid, get_most_scenic_tree, get_scenic_score};
+    use crate::render::heat;
 
-    use super::get_visible_trees;
+    use super::{get_visible_trees, scenic_heatmap, HIDDEN};
 
     #[test]
     fn test_parse_puzzle_input_to_tree_grid() {
         let input =
-String::from("30373
+"30373
 25512
 65332
 33549
-35390");
+35390";
         let expected_output = vec![
             vec![3, 0, 3, 7, 3],
             vec![2, 5, 5, 1, 2],
@@ -185,19 +118,28 @@ String::from("30373
             vec![3, 3, 5, 4, 9],
             vec![3, 5, 3, 9, 0]
         ];
-        let output = parse_puzzle_input_to_tree_grid(input);
-        assert_eq!(expected_output, output);
+        let output = parse_puzzle_input_to_tree_grid(input).unwrap();
+        assert_eq!(Grid::from_rows(expected_output).unwrap(), output);
+
+        assert!(parse_puzzle_input_to_tree_grid("303\n2a5").is_err());
+        assert!(parse_puzzle_input_to_tree_grid("303\n25").is_err());
+        assert!(parse_puzzle_input_to_tree_grid("\n").is_err());
+
+        // Blank lines before the grid are ignored, as they always have been
+        let output = parse_puzzle_input_to_tree_grid(&format!("\n\n{}\n", input)).unwrap();
+        assert_eq!(output.height(), 5);
+        assert_eq!(output[(4, 4)], 0);
     }
 
     #[test]
     fn test_get_visible_trees() {
-        let input: Vec<Vec<u32>> = vec![
+        let input = Grid::from_rows(vec![
             vec![3, 0, 3, 7, 3],
             vec![2, 5, 5, 1, 2],
             vec![6, 5, 3, 3, 2],
             vec![3, 3, 5, 4, 9],
             vec![3, 5, 3, 9, 0]
-        ];
+        ]).unwrap();
 
         let expected_out = 21;
 
@@ -207,13 +149,13 @@ String::from("30373
 
     #[test]
     fn test_get_most_scenic_tree() {
-        let input: Vec<Vec<u32>> = vec![
+        let input = Grid::from_rows(vec![
             vec![3, 0, 3, 7, 3],
             vec![2, 5, 5, 1, 2],
             vec![6, 5, 3, 3, 2],
             vec![3, 3, 5, 4, 9],
             vec![3, 5, 3, 9, 0]
-        ];
+        ]).unwrap();
 
         let expected_out = 8;
 
@@ -223,15 +165,25 @@ String::from("30373
 
     #[test]
     fn test_get_scenic_score() {
-        let input: Vec<Vec<u32>> = vec![
+        let input = Grid::from_rows(vec![
             vec![3, 0, 3, 7, 3],
             vec![2, 5, 5, 1, 2],
             vec![6, 5, 3, 3, 2],
             vec![3, 3, 5, 4, 9],
             vec![3, 5, 3, 9, 0]
-        ];
+        ]).unwrap();
 
         assert_eq!(8, get_scenic_score(&input, 3, 2));
         assert_eq!(4, get_scenic_score(&input, 1, 2));
     }
+
+    #[test]
+    fn test_scenic_heatmap() {
+        let image = scenic_heatmap(include_str!("../fixtures/day8/example.input")).unwrap();
+        assert_eq!((image.width(), image.height()), (5, 5));
+        // The most scenic tree is the brightest, and the middle tree is hidden
+        assert_eq!(image.get(2, 3), Some(heat(8, 8)));
+        assert_eq!(image.get(2, 2), Some(HIDDEN));
+        assert_eq!(image.get(0, 0), Some(heat(0, 8)));
+    }
 }
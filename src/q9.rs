@@ -1,157 +1,105 @@
-use std::{io::BufRead, collections::{HashSet, HashMap}, rc::Rc, ops::DerefMut};
+use std::{fmt::Display, io::BufRead};
 
-use crate::common::{read_file, parse_lines};
+use crate::animate::Frame;
+use crate::common::{numbered_lines, read_lines, Line, point::{Heading, Point, SparseGrid, Vector}};
+use crate::error::Result;
+use crate::render::{Image, Rgb};
+use crate::solution::Solution;
 
-enum Direction {
+const BACKGROUND: Rgb = [15, 15, 35];
+const VISITED: Rgb = [90, 90, 120];
+const START: Rgb = [60, 140, 255];
+const KNOT: Rgb = [230, 60, 60];
+const HEAD: Rgb = [255, 220, 0];
+
+/// How much of the board `rope_steps` shows around the head
+const VIEW_WIDTH: i64 = 41;
+const VIEW_HEIGHT: i64 = 21;
+
+pub enum Direction {
     Up(i32),
     Down(i32),
     Left(i32),
     Right(i32)
 }
 
-impl<T> From<T> for Direction where T : Into<String> {
-    fn from(other: T) -> Direction {
-        let other_string: String = other.into();
-        let other_split: Vec<&str> = other_string.split(' ').collect();
-        let num_steps = other_split[1].parse().unwrap();
-        match other_split[0] {
-            "R" => Direction::Right(num_steps),
-            "L" => Direction::Left(num_steps),
-            "U" => Direction::Up(num_steps),
-            "D" => Direction::Down(num_steps),
-            _ => panic!("Unknown Direction")
+impl Direction {
+    pub fn from_line(line: &Line) -> Result<Direction> {
+        let direction = line.token(0, "a direction")?;
+        let steps = line.token(1, "a number of steps")?;
+        let num_steps: i32 = line.parse(steps, "a number of steps")?;
+        if num_steps < 0 {
+            return Err(line.error(steps, "expected a positive number of steps, found"));
+        }
+        match direction {
+            "R" => Ok(Direction::Right(num_steps)),
+            "L" => Ok(Direction::Left(num_steps)),
+            "U" => Ok(Direction::Up(num_steps)),
+            "D" => Ok(Direction::Down(num_steps)),
+            _ => Err(line.error(direction, "expected one of R, L, U or D, found"))
         }
     }
 }
 
-#[derive(Hash, Eq, Clone, Copy)]
-struct Position {
-    pub x: i32,
-    pub y: i32,
-}
-
-impl PartialEq for Position {
-    fn eq(&self, other: &Self) -> bool {
-        self.x == other.x && self.y == other.y
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (letter, steps) = match self {
+            Direction::Up(steps) => ('U', steps),
+            Direction::Down(steps) => ('D', steps),
+            Direction::Left(steps) => ('L', steps),
+            Direction::Right(steps) => ('R', steps),
+        };
+        write!(f, "{} {}", letter, steps)
     }
 }
 
-impl Default for Position {
-    fn default() -> Self {
-        Self {
-            x: 0,
-            y: 0
+impl Direction {
+    /**
+     * Which way to move and how many steps to take
+     */
+    fn heading(&self) -> (Heading, i32) {
+        match *self {
+            Direction::Up(steps) => (Heading::Up, steps),
+            Direction::Down(steps) => (Heading::Down, steps),
+            Direction::Left(steps) => (Heading::Left, steps),
+            Direction::Right(steps) => (Heading::Right, steps),
         }
     }
 }
 
 struct Grid {
-    tail_positions: HashSet<Position>,
+    tail_positions: SparseGrid<()>,
     first_knot: Box<Knot>
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 struct Knot {
-    pos: Position,
+    pos: Point,
     tail: Option<Box<Knot>>
 }
 
-impl<'a> Default for Knot {
-    fn default() -> Self {
-        Self {
-            pos: Position::default(),
-            tail: None
-        }
-    }
-}
-
 impl Knot {
-    fn move_tail(&mut self) -> Option<Position> {
-        if let Some(tail) = &mut self.tail {
-            let mut moved = false;
-            if self.pos.x - tail.pos.x > 1 {
-                tail.pos.x += 1;
-                tail.pos.y += if self.pos.y > tail.pos.y {
-                    1
-                } else if self.pos.y < tail.pos.y {
-                    -1
-                } else {
-                    0
-                };
-                moved = true;
-            }
-            else if self.pos.x - tail.pos.x < -1 {
-                tail.pos.x -= 1;
-                tail.pos.y += if self.pos.y > tail.pos.y {
-                    1
-                } else if self.pos.y < tail.pos.y {
-                    -1
-                } else {
-                    0
-                };
-                moved = true;
-            }
-            else if self.pos.y - tail.pos.y > 1 {
-                tail.pos.y += 1;
-                tail.pos.x += if self.pos.x > tail.pos.x {
-                    1
-                } else if self.pos.x < tail.pos.x {
-                    -1
-                } else {
-                    0
-                };
-                moved = true;
-            }
-            else if self.pos.y - tail.pos.y < -1 {
-                tail.pos.y -= 1;
-                tail.pos.x += if self.pos.x > tail.pos.x {
-                    1
-                } else if self.pos.x < tail.pos.x {
-                    -1
-                } else {
-                    0
-                };
-                moved = true;
-            }
-            if moved {
+    /**
+     * Pull the rest of the rope after this knot, returning where the end of the
+     * rope is if it moved
+     */
+    fn move_tail(&mut self) -> Option<Point> {
+        match &mut self.tail {
+            Some(tail) => {
+                let gap = self.pos - tail.pos;
+                if gap.chebyshev() <= 1 {
+                    return None;
+                }
+                tail.pos += gap.signum();
                 tail.move_tail()
-            } else {
-                None
-            }
-        } else {
-            Some(self.pos)
-        }
-    }
-
-    pub fn apply_up(&mut self) -> Option<Position> {
-        self.pos.y += 1;
-        if self.tail.is_some() {
-            return self.move_tail();
+            },
+            None => Some(self.pos),
         }
-        return Some(self.pos);
     }
 
-    pub fn apply_down(&mut self) -> Option<Position> {
-        self.pos.y -= 1;
-        if self.tail.is_some() {
-            return self.move_tail();
-        }
-        return Some(self.pos);
-    }
-    pub fn apply_left(&mut self) -> Option<Position> {
-        self.pos.x -= 1;
-        if self.tail.is_some() {
-            return self.move_tail();
-        }
-        return Some(self.pos);
-    }
-
-    pub fn apply_right(&mut self) -> Option<Position> {
-        self.pos.x += 1;
-        if self.tail.is_some() {
-            return self.move_tail();
-        }
-        return Some(self.pos);
+    pub fn apply_step(&mut self, step: Vector) -> Option<Point> {
+        self.pos += step;
+        self.move_tail()
     }
 
     pub fn add_children(&mut self, n: usize) {
@@ -169,56 +117,9 @@ impl Default for Grid {
 }
 
 impl Grid {
-    pub fn print_grid(&self) {
-        let mut positions = Vec::new();
-        let mut positions_map = HashMap::new();
-
-        let mut knot: &Knot = &self.first_knot;
-        let mut counter = 0;
-        let mut looping = true;
-        let mut min_x = 0;
-        let mut min_y = 0;
-        let mut max_x = 0;
-        let mut max_y = 0;
-        while looping {
-            max_x = max_x.max(knot.pos.x);
-            min_x = min_x.min(knot.pos.x);
-            max_y = max_y.max(knot.pos.y);
-            min_y = min_y.min(knot.pos.y);
-
-            positions.push(knot.pos);
-            positions_map.insert(knot.pos, counter);
-            counter += 1;
-            knot = &knot.tail.as_ref().unwrap_or_else(|| {
-                looping = false;
-                &self.first_knot
-            });
-        }
-
-        for y in (min_y..max_y+1).rev() {
-            for x in min_x..max_x+1 {
-                if let Some(&number) = positions_map.get(&Position{ x: x, y:  y }) {
-                    if number == 0 {
-                        print!("H");
-                    } else {
-                        print!("{}", number);
-                    }
-                }
-                else if x == 0 && y == 0 {
-                    print!("s");
-                }
-                else {
-                    print!(".");
-                }
-            }
-            println!();
-        }
-        println!()
-    }
-
     pub fn with_n_knots(n: usize) -> Grid {
-        let mut tail_positions = HashSet::<Position>::default();
-        tail_positions.insert(Position::default());
+        let mut tail_positions = SparseGrid::new();
+        tail_positions.insert(Point::ORIGIN, ());
         let mut first_knot = Knot::default();
         first_knot.add_children(n-1);
         Self {
@@ -227,135 +128,282 @@ impl Grid {
         }
     }
 
-    fn head(&mut self) -> &mut Knot {
-        &mut self.first_knot
+    /**
+     * Move the head one step, returning where the tail ends up if it's
+     * somewhere it hasn't been before
+     */
+    fn step(&mut self, heading: Heading) -> Option<Point> {
+        let pos = self.first_knot.apply_step(heading.vector())?;
+        self.tail_positions.insert(pos, ()).is_none().then_some(pos)
     }
 
     pub fn apply_direction(&mut self, dir: &Direction) {
-        match *dir {
-            Direction::Up(steps) => self.apply_up(steps),
-            Direction::Down(steps) => self.apply_down(steps),
-            Direction::Left(steps) => self.apply_left(steps),
-            Direction::Right(steps) => self.apply_right(steps),
+        let (heading, steps) = dir.heading();
+        for _ in 0..steps {
+            self.step(heading);
         }
     }
 
-    fn apply_up(& mut self, steps: i32) {
-        if steps == 0 { return; }
-        if let Some(pos) = self.head().apply_up() {
-            self.tail_positions.insert(pos);
-        }
-        // self.print_grid();
-        self.apply_up(steps-1)
+    /**
+     * Draw the knots, the start, and the positions the tail has visited
+     * (as `#`) between the corners `min` and `max`
+     */
+    fn draw(&self, min: Point, max: Point) -> String {
+        let knots = self.knots();
+        (min.y..=max.y).rev()
+            .map(|y| (min.x..=max.x).map(|x| {
+                let point = Point::new(x, y);
+                match knots.iter().position(|&knot| knot == point) {
+                    Some(0) => 'H',
+                    Some(number) => char::from_digit(number as u32, 36).unwrap_or('#'),
+                    None if point == Point::ORIGIN => 's',
+                    None if self.tail_positions.contains(point) => '#',
+                    None => '.',
+                }
+            }).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
     }
 
-    fn apply_down(& mut self, steps: i32) {
-        if steps == 0 { return; }
-        if let Some(pos) = self.head().apply_down() {
-            self.tail_positions.insert(pos);
-        }
-        // self.print_grid();
-        self.apply_down(steps-1)
+    /**
+     * Where every knot is, from the head to the tail
+     */
+    fn knots(&self) -> Vec<Point> {
+        std::iter::successors(Some(&*self.first_knot), |knot| knot.tail.as_deref())
+            .map(|knot| knot.pos)
+            .collect()
+    }
 
+    pub fn get_num_positions(&self) -> usize {
+        self.tail_positions.len()
     }
-    fn apply_left(& mut self, steps: i32) {
-        if steps == 0 { return; }
-        if let Some(pos) = self.head().apply_left() {
-            self.tail_positions.insert(pos);
-        }
-        // self.print_grid();
-        self.apply_left(steps-1)
+}
+
+pub struct Q9;
+
+impl Solution for Q9 {
+    const DAY: u8 = 9;
+    type Input = Vec<Direction>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        numbered_lines(Q9::DAY, input)
+            .map(|line| Direction::from_line(&line))
+            .collect()
     }
 
-    fn apply_right(& mut self, steps: i32) {
-        if steps == 0 { return; }
-        if let Some(pos) = self.head().apply_right() {
-            self.tail_positions.insert(pos);
-        }
-        // self.print_grid();
-        self.apply_right(steps-1)
+    fn part1(directions: &Self::Input) -> usize {
+        count_tail_positions(directions, 2)
     }
 
-    pub fn get_num_positions(&self) -> usize {
-        self.tail_positions.len()
+    fn part2(directions: &Self::Input) -> usize {
+        count_tail_positions(directions, 10)
     }
 }
 
-pub fn solve_q9() {
-    let input = read_file("./puzzle_9.input");
-    let directions = parse_lines(&input).into_iter()
-        .map(|line| Direction::from(line))
-        .collect::<Vec<Direction>>();
-
-    let mut grid = Grid::default();
+fn count_tail_positions(directions: &[Direction], num_knots: usize) -> usize {
+    let mut grid = Grid::with_n_knots(num_knots);
     for direction in directions.iter() {
-        grid.apply_direction(&direction);
+        grid.apply_direction(direction);
+    }
+    grid.get_num_positions()
+}
+
+/**
+ * Count the positions the tail visits while reading the directions one line at
+ * a time, so only the visited positions are kept in memory
+ */
+pub fn count_tail_positions_in<R: BufRead>(reader: R, num_knots: usize) -> Result<usize> {
+    let mut grid = Grid::with_n_knots(num_knots.max(1));
+    for line in read_lines(Q9::DAY, reader) {
+        let line = line?;
+        grid.apply_direction(&Direction::from_line(&line.as_line())?);
     }
-    println!("Solution 1: {}", grid.get_num_positions());
+    Ok(grid.get_num_positions())
+}
+
+/**
+ * Pictures of the rope after each line of directions, with a pixel per
+ * position. Every frame covers the whole area the rope moves over, and shows
+ * the positions the tail has visited so far. The rope is moved up front to
+ * find that area, but each picture is only drawn when it's asked for
+ */
+pub fn rope_frames(input: &str, num_knots: usize) -> Result<impl Iterator<Item = Image>> {
+    let mut grid = Grid::with_n_knots(num_knots.max(1));
+    let mut visited = vec![Point::ORIGIN];
+    let mut snapshots = vec![(grid.knots(), visited.len())];
+    for direction in Q9::parse(input)? {
+        let (heading, steps) = direction.heading();
+        for _ in 0..steps {
+            visited.extend(grid.step(heading));
+        }
+        snapshots.push((grid.knots(), visited.len()));
+    }
+
+    let mut area = SparseGrid::new();
+    for point in snapshots.iter().flat_map(|(knots, _)| knots) {
+        area.insert(*point, ());
+    }
+    let (min, max) = area.bounds().expect("the rope has at least one knot");
+    let pixel = move |point: Point| ((point.x - min.x) as usize, (max.y - point.y) as usize);
+    let (width, height) = pixel(Point::new(max.x, min.y));
+
+    // The visited positions only ever grow, so they're drawn once onto a
+    // canvas that each frame starts from
+    let mut canvas = Image::new(width + 1, height + 1, BACKGROUND);
+    let mut drawn = 0;
+    Ok(snapshots.into_iter().map(move |(knots, num_visited)| {
+        for &point in &visited[drawn..num_visited] {
+            let (x, y) = pixel(point);
+            canvas.set(x, y, VISITED);
+        }
+        drawn = num_visited;
+        let mut image = canvas.clone();
+        let (x, y) = pixel(Point::ORIGIN);
+        image.set(x, y, START);
+        // Draw from the tail so the head ends up on top
+        for (number, &knot) in knots.iter().enumerate().rev() {
+            let (x, y) = pixel(knot);
+            image.set(x, y, if number == 0 { HEAD } else { KNOT });
+        }
+        image
+    }))
+}
 
-    let mut grid = Grid::with_n_knots(10);
-    for direction in directions {
-        grid.apply_direction(&direction);
+/**
+ * The rope drawn as text at the start and after every step of the head, in a
+ * window that follows the head around
+ */
+pub fn rope_steps(input: &str, num_knots: usize) -> Result<Vec<Frame>> {
+    let mut grid = Grid::with_n_knots(num_knots.max(1));
+    let view = |grid: &Grid| {
+        let corner = Vector::new(VIEW_WIDTH / 2, VIEW_HEIGHT / 2);
+        grid.draw(grid.first_knot.pos - corner, grid.first_knot.pos + corner)
+    };
+    let mut frames = vec![Frame::new(view(&grid), "the start")];
+    for direction in Q9::parse(input)? {
+        let (heading, steps) = direction.heading();
+        for step in 1..=steps {
+            grid.step(heading);
+            let status = format!("{} (step {} of {}), the tail has visited {}", direction, step, steps, grid.get_num_positions());
+            frames.push(Frame::new(view(&grid), status));
+        }
     }
-    println!("Solution 2: {}", grid.get_num_positions());
+    Ok(frames)
+}
 
+/**
+ * The number of positions the tail of a two knot rope visits
+ */
+pub fn part1(input: &str) -> Result<usize> {
+    Ok(Q9::part1(&Q9::parse(input)?))
 }
 
+/**
+ * The number of positions the tail of a ten knot rope visits
+ */
+pub fn part2(input: &str) -> Result<usize> {
+    Ok(Q9::part2(&Q9::parse(input)?))
+}
+
+#[cfg(test)]
 mod test {
-    use crate::common::parse_lines;
+    use std::io::Cursor;
+
+    use crate::common::numbered_lines;
+    use crate::error::Result;
 
-    use super::{Direction, Grid};
+    use crate::render::Image;
+
+    use super::{Direction, Grid, count_tail_positions_in, rope_frames, rope_steps, Frame, BACKGROUND, HEAD, START, VISITED};
 
     #[test]
     fn test_example_problem() {
-        let directions = parse_lines("R 4
-U 4
-L 3
-D 1
-R 4
-D 1
-L 5
-R 2")
-    .into_iter()
-    .map(|line| Direction::from(line))
-    .collect::<Vec<Direction>>();
+        let directions = numbered_lines(9, include_str!("../fixtures/day9/example.input"))
+            .map(|line| Direction::from_line(&line))
+            .collect::<Result<Vec<Direction>>>()
+            .unwrap();
 
         let mut grid = Grid::default();
         for direction in directions.iter() {
-            grid.apply_direction(&direction);
+            grid.apply_direction(direction);
         }
         assert_eq!(13, grid.get_num_positions());
 
         let mut grid = Grid::with_n_knots(10);
         for direction in directions.iter() {
-            grid.apply_direction(&direction);
-            println!();
-            grid.print_grid();
+            grid.apply_direction(direction);
         }
         assert_eq!(1, grid.get_num_positions());
-
     }
 
     #[test]
     fn test_example_problem_2() {
-        let directions = parse_lines("R 5
-U 8
-L 8
-D 3
-R 17
-D 10
-L 25
-U 20")
-    .into_iter()
-    .map(|line| Direction::from(line))
-    .collect::<Vec<Direction>>();
+        let directions = numbered_lines(9, include_str!("../fixtures/day9/example_2.input"))
+            .map(|line| Direction::from_line(&line))
+            .collect::<Result<Vec<Direction>>>()
+            .unwrap();
+
         let mut grid = Grid::with_n_knots(10);
         for direction in directions.iter() {
-            grid.apply_direction(&direction);
-            println!("=====");
-            grid.print_grid();
+            grid.apply_direction(direction);
         }
         assert_eq!(36, grid.get_num_positions());
+    }
 
+    #[test]
+    fn test_count_tail_positions_in() {
+        let input = include_str!("../fixtures/day9/example_2.input");
+        assert_eq!(count_tail_positions_in(Cursor::new(input), 10).unwrap(), 36);
+        assert!(count_tail_positions_in(Cursor::new("R 5\nX 2\n"), 10).is_err());
+
+        // A rope needs at least one knot, which is then its own tail
+        assert_eq!(count_tail_positions_in(Cursor::new("R 2\nU 1\n"), 0).unwrap(), 4);
+        assert_eq!(count_tail_positions_in(Cursor::new("R 2\nU 1\n"), 1).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_rope_frames() {
+        let frames: Vec<Image> = rope_frames(include_str!("../fixtures/day9/example.input"), 2).unwrap().collect();
+        // The starting position, then one frame per line
+        assert_eq!(frames.len(), 9);
+        // The head reaches 5 right and 4 up, but never goes left of or below the start
+        assert!(frames.iter().all(|frame| (frame.width(), frame.height()) == (6, 5)));
+        assert_eq!(frames[0].get(0, 4), Some(HEAD));
+        assert_eq!(frames[1].get(4, 4), Some(HEAD));
+        assert_eq!(frames[1].get(0, 4), Some(START));
+        assert_eq!(frames[1].get(2, 4), Some(VISITED));
+
+        // Both knots finish on positions the tail has already visited
+        let last = frames.last().unwrap();
+        let visited = (0..6).flat_map(|x| (0..5).map(move |y| (x, y)))
+            .filter(|&(x, y)| last.get(x, y) != Some(BACKGROUND))
+            .count();
+        assert_eq!(visited, 13);
+    }
+
+    #[test]
+    fn test_rope_steps() {
+        let frames = rope_steps(include_str!("../fixtures/day9/example.input"), 2).unwrap();
+        // The start, then one frame per step: 4 + 4 + 3 + 1 + 4 + 1 + 5 + 2
+        assert_eq!(frames.len(), 25);
+        assert_eq!(frames[2].status, "R 4 (step 2 of 4), the tail has visited 2");
+        assert_eq!(frames.last().unwrap().status, "R 2 (step 2 of 2), the tail has visited 13");
+
+        // The head stays in the middle of the view
+        let middle = |frame: &Frame| frame.picture.lines().nth(10).unwrap().to_string();
+        assert_eq!(frames[2].picture.lines().count(), 21);
+        assert_eq!(&middle(&frames[0])[18..23], "..H..");
+        assert_eq!(&middle(&frames[2])[18..23], "s1H..");
+        assert_eq!(&middle(&frames[4])[16..23], "s##1H..");
+    }
+
+    #[test]
+    fn test_direction_errors() {
+        let mut lines = numbered_lines(9, "X 4\nR\nU -");
+        assert!(Direction::from_line(&lines.next().unwrap()).is_err());
+        assert!(Direction::from_line(&lines.next().unwrap()).is_err());
+        assert!(Direction::from_line(&lines.next().unwrap()).is_err());
     }
 }
@@ -0,0 +1,262 @@
+use std::{collections::HashMap, fs::File, io::BufWriter, path::Path};
+
+use crate::common::grid::Grid;
+use crate::error::{Error, Result};
+
+/**
+ * A color as red, green and blue
+ */
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+
+/**
+ * The left, top, right and bottom edges of a rectangle of pixels, inclusive
+ */
+type Area = (usize, usize, usize, usize);
+
+/**
+ * A picture to save as a PNG or as one frame of a GIF
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, fill: Rgb) -> Self {
+        Self { width, height, pixels: vec![fill; width * height] }
+    }
+
+    /**
+     * One pixel per cell of `grid`, colored by `color`
+     */
+    pub fn from_grid<T>(grid: &Grid<T>, color: impl Fn(&T) -> Rgb) -> Self {
+        Self {
+            width: grid.width(),
+            height: grid.height(),
+            pixels: grid.iter().map(|(_, cell)| color(cell)).collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<Rgb> {
+        (x < self.width && y < self.height).then(|| self.pixels[y * self.width + x])
+    }
+
+    /**
+     * Color the pixel `x` across and `y` down, ignoring pixels outside the image
+     */
+    pub fn set(&mut self, x: usize, y: usize, color: Rgb) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = color;
+        }
+    }
+
+    /**
+     * Blow every pixel up into a `factor` by `factor` square, since a pixel per
+     * cell is too small to see
+     */
+    pub fn scale(&self, factor: usize) -> Self {
+        let mut scaled = Self::new(self.width * factor, self.height * factor, BLACK);
+        for y in 0..scaled.height {
+            for x in 0..scaled.width {
+                scaled.pixels[y * scaled.width + x] = self.pixels[(y / factor) * self.width + x / factor];
+            }
+        }
+        scaled
+    }
+
+    /**
+     * The pixels inside `area`, row by row
+     */
+    fn area(&self, (left, top, right, bottom): Area) -> impl Iterator<Item = &Rgb> {
+        (top..=bottom).flat_map(move |y| &self.pixels[y * self.width + left..=y * self.width + right])
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        self.pixels.iter().flatten().copied().collect()
+    }
+}
+
+/**
+ * A color from dark blue for 0 through to yellow for `max`
+ */
+pub fn heat(value: u64, max: u64) -> Rgb {
+    let t = if max == 0 { 0.0 } else { value.min(max) as f64 / max as f64 };
+    let channel = |from: f64, to: f64| (from + (to - from) * t).round() as u8;
+    [channel(20.0, 255.0), channel(20.0, 220.0), channel(90.0, 0.0)]
+}
+
+fn render_error(path: &Path, reason: impl ToString) -> Error {
+    Error::Render { path: path.to_path_buf(), reason: reason.to_string() }
+}
+
+/**
+ * The width and height of `image`, which must be small enough for the image
+ * formats
+ */
+fn dimensions(path: &Path, image: &Image) -> Result<(u16, u16)> {
+    match (u16::try_from(image.width), u16::try_from(image.height)) {
+        (Ok(width), Ok(height)) if width > 0 && height > 0 => Ok((width, height)),
+        _ => Err(render_error(path, format!("a {}x{} image can't be saved", image.width, image.height))),
+    }
+}
+
+fn create(path: &Path) -> Result<BufWriter<File>> {
+    File::create(path).map(BufWriter::new).map_err(|why| Error::io(path, why))
+}
+
+pub fn save_png(path: impl AsRef<Path>, image: &Image) -> Result<()> {
+    let path = path.as_ref();
+    let (width, height) = dimensions(path, image)?;
+    let mut encoder = png::Encoder::new(create(path)?, width.into(), height.into());
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|why| render_error(path, why))?;
+    writer.write_image_data(&image.bytes()).map_err(|why| render_error(path, why))?;
+    writer.finish().map_err(|why| render_error(path, why))
+}
+
+/**
+ * The smallest rectangle holding every pixel that differs between two images
+ * of the same size
+ */
+fn changed_area(before: &Image, after: &Image) -> Option<Area> {
+    let mut area: Option<Area> = None;
+    for (index, (old, new)) in before.pixels.iter().zip(&after.pixels).enumerate() {
+        if old != new {
+            let (x, y) = (index % after.width, index / after.width);
+            area = Some(match area {
+                None => (x, y, x, y),
+                Some((left, top, right, bottom)) => (left.min(x), top.min(y), right.max(x), bottom.max(y)),
+            });
+        }
+    }
+    area
+}
+
+/**
+ * Save `frames` as a looping animation, showing each one for `delay`
+ * hundredths of a second. Frames are written as they come, so only the last
+ * one is held onto, and every frame must be the same size as the first. Each
+ * frame carries its own palette, and GIFs are limited to 256 colors a
+ * palette, which is plenty for the handful each puzzle uses
+ */
+pub fn save_gif(path: impl AsRef<Path>, frames: impl IntoIterator<Item = Image>, delay: u16) -> Result<()> {
+    let path = path.as_ref();
+    let mut frames = frames.into_iter();
+    let first = frames.next().ok_or_else(|| render_error(path, "there are no frames to draw"))?;
+    let (width, height) = dimensions(path, &first)?;
+    let mut encoder = gif::Encoder::new(create(path)?, width, height, &[]).map_err(|why| render_error(path, why))?;
+    encoder.set_repeat(gif::Repeat::Infinite).map_err(|why| render_error(path, why))?;
+
+    let mut previous: Option<Image> = None;
+    for image in std::iter::once(first).chain(frames) {
+        // Only the part of each frame that changed is stored, drawn over the last
+        let area = match &previous {
+            None => (0, 0, image.width - 1, image.height - 1),
+            Some(previous) if (previous.width, previous.height) != (image.width, image.height) => {
+                return Err(render_error(path, "every frame must be the same size"));
+            },
+            Some(previous) => changed_area(previous, &image).unwrap_or((0, 0, 0, 0)),
+        };
+
+        let mut palette: Vec<Rgb> = Vec::new();
+        let mut indexes = HashMap::new();
+        let mut pixels = Vec::new();
+        for color in image.area(area) {
+            let index = match indexes.get(color) {
+                Some(&index) => index,
+                None if palette.len() == 256 => return Err(render_error(path, "a GIF frame can't hold more than 256 colors")),
+                None => {
+                    indexes.insert(*color, palette.len() as u8);
+                    palette.push(*color);
+                    palette.len() as u8 - 1
+                },
+            };
+            pixels.push(index);
+        }
+
+        let (left, top, right, bottom) = area;
+        // The area is inside a frame whose size fits in a u16
+        let mut frame = gif::Frame::from_indexed_pixels((right - left + 1) as u16, (bottom - top + 1) as u16, pixels, None);
+        frame.palette = Some(palette.into_iter().flatten().collect());
+        frame.left = left as u16;
+        frame.top = top as u16;
+        frame.delay = delay;
+        frame.dispose = gif::DisposalMethod::Keep;
+        encoder.write_frame(&frame).map_err(|why| render_error(path, why))?;
+        previous = Some(image);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs::{self, File}};
+
+    use crate::common::grid::Grid;
+
+    use super::{heat, save_gif, save_png, Image, BLACK, WHITE};
+
+    #[test]
+    fn test_image() {
+        let grid = Grid::from_rows(vec![vec![true, false], vec![false, false]]).unwrap();
+        let image = Image::from_grid(&grid, |&on| if on { WHITE } else { BLACK });
+        assert_eq!(image.get(0, 0), Some(WHITE));
+        assert_eq!(image.get(1, 0), Some(BLACK));
+        assert_eq!(image.get(2, 0), None);
+
+        let scaled = image.scale(3);
+        assert_eq!((scaled.width(), scaled.height()), (6, 6));
+        assert_eq!(scaled.get(2, 2), Some(WHITE));
+        assert_eq!(scaled.get(3, 2), Some(BLACK));
+
+        assert_eq!(heat(0, 10), [20, 20, 90]);
+        assert_eq!(heat(10, 10), [255, 220, 0]);
+        assert_eq!(heat(20, 10), heat(10, 10));
+    }
+
+    #[test]
+    fn test_save() {
+        let dir = env::temp_dir().join(format!("advent-of-code-render-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut image = Image::new(3, 2, BLACK);
+        image.set(2, 1, WHITE);
+        save_png(dir.join("still.png"), &image).unwrap();
+        let mut reader = png::Decoder::new(File::open(dir.join("still.png")).unwrap()).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (3, 2));
+        assert_eq!(&pixels[15..18], &WHITE);
+
+        // Only the changed pixel is stored after the first frame, and an
+        // unchanged frame still takes its turn
+        let frames = vec![Image::new(3, 2, BLACK), image.clone(), image.clone()];
+        save_gif(dir.join("moving.gif"), frames, 5).unwrap();
+        let mut decoder = gif::DecodeOptions::new().read_info(File::open(dir.join("moving.gif")).unwrap()).unwrap();
+        let mut stored = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 5);
+            assert!(frame.palette.is_some());
+            stored.push((frame.left, frame.top, frame.width, frame.height));
+        }
+        assert_eq!(stored, vec![(0, 0, 3, 2), (2, 1, 1, 1), (0, 0, 1, 1)]);
+
+        assert!(save_gif(dir.join("empty.gif"), Vec::new(), 5).is_err());
+        assert!(save_gif(dir.join("mixed.gif"), vec![image, Image::new(2, 2, BLACK)], 5).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,228 @@
+use std::{fs, path::{Path, PathBuf}};
+
+use crate::common::read_file;
+use crate::error::{Error, Result};
+use crate::fixtures::fixture_dir;
+use crate::input_file_name;
+
+const MODULE_TEMPLATE: &str = "use crate::common::numbered_lines;
+use crate::error::Result;
+use crate::solution::Solution;
+
+pub struct Q{day};
+
+impl Solution for Q{day} {
+    const DAY: u8 = {day};
+    type Input = Vec<String>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(numbered_lines(Self::DAY, input).map(|line| line.text.to_string()).collect())
+    }
+
+    fn part1(_input: &Self::Input) -> usize {
+        0
+    }
+
+    fn part2(_input: &Self::Input) -> usize {
+        0
+    }
+}
+
+/**
+ * The answer to part 1
+ */
+pub fn part1(input: &str) -> Result<usize> {
+    Ok(Q{day}::part1(&Q{day}::parse(input)?))
+}
+
+/**
+ * The answer to part 2
+ */
+pub fn part2(input: &str) -> Result<usize> {
+    Ok(Q{day}::part2(&Q{day}::parse(input)?))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::solution::Solution;
+
+    use super::Q{day};
+
+    #[test]
+    fn test_example() {
+        let input = Q{day}::parse(include_str!(\"../fixtures/day{day}/example.input\")).unwrap();
+        assert_eq!(Q{day}::part1(&input), 0);
+        assert_eq!(Q{day}::part2(&input), 0);
+    }
+}
+";
+
+const DAYS_START: &str = "pub static DAYS: &[Day] = &[\n";
+
+fn module_name(day: u8) -> String {
+    format!("q{}", day)
+}
+
+fn scaffold_error(path: &Path, reason: impl Into<String>) -> Error {
+    Error::Scaffold { path: path.to_path_buf(), reason: reason.into() }
+}
+
+/**
+ * The source of a freshly generated module for `day`
+ */
+pub fn module_source(day: u8) -> String {
+    MODULE_TEMPLATE.replace("{day}", &day.to_string())
+}
+
+/**
+ * Add `pub mod qN;` and a `DAYS` entry for `day` to the source of `lib.rs`,
+ * keeping both in day order
+ */
+pub fn register_day(lib: &str, day: u8) -> std::result::Result<String, String> {
+    let module = module_name(day);
+    let declaration = format!("pub mod {};", module);
+    if lib.lines().any(|line| line.trim() == declaration) {
+        return Err(format!("day {} is already registered", day));
+    }
+
+    let day_of = |line: &str| line.strip_prefix("pub mod q")?.strip_suffix(';')?.parse::<u8>().ok();
+    let mut offset = 0;
+    let mut insert_at = None;
+    for line in lib.split_inclusive('\n') {
+        match day_of(line.trim_end()) {
+            Some(other) if other < day => insert_at = Some(offset + line.len()),
+            Some(_) if insert_at.is_none() => insert_at = Some(offset),
+            _ => {},
+        }
+        offset += line.len();
+    }
+    let insert_at = insert_at.ok_or("no day modules are declared")?;
+    let mut lib = lib.to_string();
+    if lib[..insert_at].ends_with(";\n") {
+        lib.insert_str(insert_at, &format!("\n{}\n", declaration));
+    } else {
+        lib.insert_str(insert_at, &format!("{}\n\n", declaration));
+    }
+
+    let start = lib.find(DAYS_START).ok_or("the DAYS registry is missing")? + DAYS_START.len();
+    let end = start + lib[start..].find("];").ok_or("the DAYS registry is never closed")?;
+    let entry = format!("    Day::new::<{}::Q{}>(),\n", module, day);
+    let mut entries: Vec<&str> = lib[start..end].split_inclusive('\n').collect();
+    let position = entries.iter()
+        .position(|line| {
+            let other = line.trim().strip_prefix("Day::new::<q").and_then(|rest| rest.split_once(':'));
+            matches!(other.and_then(|(other, _)| other.parse::<u8>().ok()), Some(other) if other > day)
+        })
+        .unwrap_or(entries.len());
+    entries.insert(position, &entry);
+    let entries = entries.concat();
+    lib.replace_range(start..end, &entries);
+    Ok(lib)
+}
+
+/**
+ * Generate `src/qN.rs` under `root`, register it in `src/lib.rs`, and create an
+ * empty example fixture and an empty input in `input_dir` if there aren't any.
+ * Nothing is written if the module already exists. Returns every file that was
+ * created or changed
+ */
+pub fn new_day<P: AsRef<Path>, Q: AsRef<Path>>(root: P, input_dir: Q, day: u8) -> Result<Vec<PathBuf>> {
+    let src = root.as_ref().join("src");
+    let module_path = src.join(format!("{}.rs", module_name(day)));
+    let lib_path = src.join("lib.rs");
+    let input_path = input_dir.as_ref().join(input_file_name(day));
+
+    if !(1..=25).contains(&day) {
+        return Err(scaffold_error(&module_path, format!("{} is not a day of advent", day)));
+    }
+    if module_path.exists() {
+        return Err(scaffold_error(&module_path, "the module already exists"));
+    }
+    let lib = register_day(&read_file(&lib_path)?, day).map_err(|reason| scaffold_error(&lib_path, reason))?;
+
+    let write = |path: &Path, contents: &str| fs::write(path, contents).map_err(|why| Error::io(path, why));
+    write(&module_path, &module_source(day))?;
+    write(&lib_path, &lib)?;
+    let mut written = vec![module_path, lib_path];
+
+    let fixtures = fixture_dir(root.as_ref().join("fixtures"), day);
+    if !fixtures.exists() {
+        fs::create_dir_all(&fixtures).map_err(|why| Error::io(&fixtures, why))?;
+        for (name, contents) in [("example.input", ""), ("answers.toml", "[example]\n")] {
+            write(&fixtures.join(name), contents)?;
+            written.push(fixtures.join(name));
+        }
+    }
+    if !input_path.exists() {
+        write(&input_path, "")?;
+        written.push(input_path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use super::{module_source, new_day, register_day};
+
+    const LIB: &str = "pub mod common;
+
+pub mod q1;
+
+pub mod q3;
+
+use solution::Day;
+
+pub static DAYS: &[Day] = &[
+    Day::new::<q1::Q1>(),
+    Day::new::<q3::Q3>(),
+];
+";
+
+    #[test]
+    fn test_register_day() {
+        assert_eq!(register_day(LIB, 2).unwrap(), "pub mod common;
+
+pub mod q1;
+
+pub mod q2;
+
+pub mod q3;
+
+use solution::Day;
+
+pub static DAYS: &[Day] = &[
+    Day::new::<q1::Q1>(),
+    Day::new::<q2::Q2>(),
+    Day::new::<q3::Q3>(),
+];
+");
+        let lib = register_day(LIB, 12).unwrap();
+        assert!(lib.contains("pub mod q3;\n\npub mod q12;\n\nuse"));
+        assert!(lib.contains("Day::new::<q3::Q3>(),\n    Day::new::<q12::Q12>(),\n];"));
+        assert!(register_day(LIB, 3).is_err());
+    }
+
+    #[test]
+    fn test_new_day_refuses_to_overwrite() {
+        let root = env::temp_dir().join(format!("advent-of-code-scaffold-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), LIB).unwrap();
+
+        let written = new_day(&root, &root, 2).unwrap();
+        assert_eq!(written.len(), 5);
+        assert_eq!(fs::read_to_string(root.join("fixtures/day2/answers.toml")).unwrap(), "[example]\n");
+        assert_eq!(fs::read_to_string(root.join("src/q2.rs")).unwrap(), module_source(2));
+        assert_eq!(fs::read_to_string(root.join("puzzle_2.input")).unwrap(), "");
+
+        let lib = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        assert!(new_day(&root, &root, 2).is_err());
+        assert!(new_day(&root, &root, 3).is_err());
+        assert_eq!(fs::read_to_string(root.join("src/lib.rs")).unwrap(), lib);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
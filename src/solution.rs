@@ -0,0 +1,164 @@
+use std::{any::Any, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+
+/**
+ * The answer to one part of a puzzle. Most days produce a number, but some
+ * (like the crate stacks in Q5 or the CRT image in Q10) produce text
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+macro_rules! answer_from_number {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Number(n as i64)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_number!(u32, u64, usize, i32, i64);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+/**
+ * A solution to a single day of the advent calendar.
+ *
+ * The puzzle input is parsed once and then shared by both parts.
+ */
+pub trait Solution {
+    const DAY: u8;
+    type Input;
+    type Answer1: Into<Answer>;
+    type Answer2: Into<Answer>;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+    fn part1(input: &Self::Input) -> Self::Answer1;
+    fn part2(input: &Self::Input) -> Self::Answer2;
+}
+
+/**
+ * Parsed input for a day whose concrete type has been erased
+ */
+pub struct Parsed(Box<dyn Any>);
+
+/**
+ * A type erased handle to a `Solution`, used to enumerate the days uniformly
+ */
+pub struct Day {
+    pub day: u8,
+    parse: fn(&str) -> Result<Parsed>,
+    part1: fn(&Parsed) -> Answer,
+    part2: fn(&Parsed) -> Answer,
+}
+
+fn parse_erased<S: Solution>(input: &str) -> Result<Parsed> where S::Input: 'static {
+    Ok(Parsed(Box::new(S::parse(input)?)))
+}
+
+fn downcast<S: Solution>(parsed: &Parsed) -> &S::Input where S::Input: 'static {
+    parsed.0.downcast_ref().expect("parsed input belongs to a different day")
+}
+
+fn part1_erased<S: Solution>(parsed: &Parsed) -> Answer where S::Input: 'static {
+    S::part1(downcast::<S>(parsed)).into()
+}
+
+fn part2_erased<S: Solution>(parsed: &Parsed) -> Answer where S::Input: 'static {
+    S::part2(downcast::<S>(parsed)).into()
+}
+
+impl Day {
+    pub const fn new<S: Solution>() -> Self where S::Input: 'static {
+        Self {
+            day: S::DAY,
+            parse: parse_erased::<S>,
+            part1: part1_erased::<S>,
+            part2: part2_erased::<S>,
+        }
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Parsed> {
+        (self.parse)(input)
+    }
+
+    pub fn part1(&self, parsed: &Parsed) -> Answer {
+        (self.part1)(parsed)
+    }
+
+    pub fn part2(&self, parsed: &Parsed) -> Answer {
+        (self.part2)(parsed)
+    }
+
+    /**
+     * Parse the input and solve both parts
+     */
+    pub fn run(&self, input: &str) -> Result<(Answer, Answer)> {
+        let parsed = self.parse(input)?;
+        Ok((self.part1(&parsed), self.part2(&parsed)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Answer, Day, Solution};
+    use crate::error::Result;
+
+    struct Double;
+
+    impl Solution for Double {
+        const DAY: u8 = 0;
+        type Input = Vec<u64>;
+        type Answer1 = u64;
+        type Answer2 = String;
+
+        fn parse(input: &str) -> Result<Self::Input> {
+            Ok(input.split(',').map(|n| n.parse().unwrap()).collect())
+        }
+
+        fn part1(input: &Self::Input) -> u64 {
+            input.iter().map(|n| n * 2).sum()
+        }
+
+        fn part2(input: &Self::Input) -> String {
+            input.iter().map(|n| n.to_string()).collect()
+        }
+    }
+
+    #[test]
+    fn test_day_erases_solution() {
+        let day = Day::new::<Double>();
+        let (part1, part2) = day.run("1,2,3").unwrap();
+        assert_eq!(part1, Answer::Number(12));
+        assert_eq!(part2, Answer::Text("123".to_string()));
+    }
+}
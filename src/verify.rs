@@ -0,0 +1,144 @@
+use std::{collections::BTreeMap, fmt::Display, path::Path, time::{Duration, Instant}};
+
+use serde::Deserialize;
+
+use crate::common::read_file;
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Day};
+use crate::input_file_name;
+
+/**
+ * The accepted answers for a single day. Either part may not be known yet
+ */
+#[derive(Debug, Default, Deserialize)]
+pub struct DayAnswers {
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+}
+
+/**
+ * The accepted answers for every day, as recorded in `answers.toml`
+ */
+#[derive(Debug, Default)]
+pub struct Answers(BTreeMap<u8, DayAnswers>);
+
+impl Answers {
+    pub fn parse(s: &str) -> std::result::Result<Self, String> {
+        let days: BTreeMap<String, DayAnswers> = toml::from_str(s).map_err(|why| why.message().to_string())?;
+        let mut answers = BTreeMap::new();
+        for (day, day_answers) in days {
+            let day: u8 = day.parse().map_err(|_| format!("`{}` is not a day", day))?;
+            answers.insert(day, day_answers);
+        }
+        Ok(Answers(answers))
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = read_file(path)?;
+        Self::parse(&contents).map_err(|reason| Error::Answers { path: path.to_path_buf(), reason })
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&Answer> {
+        let answers = self.0.get(&day)?;
+        match part {
+            1 => answers.part1.as_ref(),
+            2 => answers.part2.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Status {
+    Pass,
+    Fail { expected: Answer },
+    /// There is no recorded answer to check against
+    Missing,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail { .. } => write!(f, "FAIL"),
+            Status::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PartReport {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Answer,
+    pub status: Status,
+    /// Time spent parsing the input and solving this part
+    pub elapsed: Duration,
+}
+
+/**
+ * Solve both parts of a day and check them against the recorded answers
+ */
+pub fn verify_day(day: &Day, input: &str, answers: &Answers) -> Result<[PartReport; 2]> {
+    let start = Instant::now();
+    let parsed = day.parse(input)?;
+    let parse_time = start.elapsed();
+
+    let check = |part: u8| {
+        let start = Instant::now();
+        let answer = if part == 1 { day.part1(&parsed) } else { day.part2(&parsed) };
+        let elapsed = parse_time + start.elapsed();
+        let status = match answers.get(day.day, part) {
+            None => Status::Missing,
+            Some(expected) if *expected == answer => Status::Pass,
+            Some(expected) => Status::Fail { expected: expected.clone() },
+        };
+        PartReport { day: day.day, part, answer, status, elapsed }
+    };
+    Ok([check(1), check(2)])
+}
+
+/**
+ * Verify a day against its `puzzle_N.input` in `input_dir`
+ */
+pub fn verify_day_in<P: AsRef<Path>>(day: &Day, input_dir: P, answers: &Answers) -> Result<[PartReport; 2]> {
+    let input = read_file(input_dir.as_ref().join(input_file_name(day.day)))?;
+    verify_day(day, &input, answers)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::get_day;
+    use crate::solution::Answer;
+
+    use super::{Answers, Status, verify_day};
+
+    #[test]
+    fn test_verify_day() {
+        let answers = Answers::parse("
+[4]
+part1 = 2
+part2 = 5
+[5]
+part1 = \"CMZ\"
+").unwrap();
+        assert_eq!(answers.get(5, 1), Some(&Answer::Text("CMZ".to_string())));
+        assert_eq!(answers.get(5, 2), None);
+
+        let input = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8\n";
+        let [part1, part2] = verify_day(get_day(4).unwrap(), input, &answers).unwrap();
+        assert_eq!(part1.status, Status::Pass);
+        assert_eq!(part2.status, Status::Fail { expected: Answer::Number(5) });
+        assert_eq!(part2.answer, Answer::Number(4));
+
+        let [_, part2] = verify_day(get_day(4).unwrap(), input, &Answers::default()).unwrap();
+        assert_eq!(part2.status, Status::Missing);
+    }
+
+    #[test]
+    fn test_parse_answers_errors() {
+        assert!(Answers::parse("[x]\npart1 = 1").is_err());
+        assert!(Answers::parse("[1]\npart1 = ").is_err());
+    }
+}
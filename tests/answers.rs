@@ -0,0 +1,25 @@
+//! Check every day against its real input and the answers recorded in `answers.toml`
+
+mod common;
+
+use advent_of_code::{solution::Day, verify::{Answers, Status, verify_day_in}};
+
+/**
+ * Days with no answers recorded yet, like one just added with `new-day`, are
+ * left out; once either part is recorded both have to pass
+ */
+fn check_day(day: &Day) {
+    let root = env!("CARGO_MANIFEST_DIR");
+    let answers = Answers::load(format!("{}/answers.toml", root)).unwrap();
+    if answers.get(day.day, 1).is_none() && answers.get(day.day, 2).is_none() {
+        return;
+    }
+    for report in verify_day_in(day, root, &answers).unwrap() {
+        assert_eq!(report.status, Status::Pass, "day {} part {} answered {}", report.day, report.part, report.answer);
+    }
+}
+
+#[test]
+fn every_day() {
+    common::check_every_day(check_day);
+}
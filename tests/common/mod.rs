@@ -0,0 +1,18 @@
+//! Helpers shared by the integration tests
+
+use std::panic::{self, AssertUnwindSafe};
+
+use advent_of_code::{solution::Day, DAYS};
+
+/**
+ * Run `check` on every registered day, so a day added with `new-day` is
+ * checked without listing it here. Every day is checked even after one fails,
+ * and the failures are reported together
+ */
+pub fn check_every_day(check: impl Fn(&'static Day)) {
+    let failed: Vec<u8> = DAYS.iter()
+        .filter(|&day| panic::catch_unwind(AssertUnwindSafe(|| check(day))).is_err())
+        .map(|day| day.day)
+        .collect();
+    assert!(failed.is_empty(), "days {:?} failed, see above", failed);
+}
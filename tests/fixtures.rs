@@ -0,0 +1,35 @@
+//! Check every day against the example inputs under `fixtures/dayN/`
+
+mod common;
+
+use std::fs;
+
+use advent_of_code::{fixtures::{check_fixture, load_fixtures}, get_day, solution::Day, verify::Status};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures");
+
+fn check_day(day: &Day) {
+    let fixtures = load_fixtures(FIXTURES, day.day).unwrap();
+    assert!(!fixtures.is_empty(), "day {} has no fixtures", day.day);
+    for fixture in &fixtures {
+        let reports = check_fixture(day, fixture).unwrap_or_else(|why| panic!("fixture {}: {}", fixture.name, why));
+        for report in reports {
+            assert!(!matches!(report.status, Status::Fail { .. }),
+                "day {} fixture {} part {} answered {}", day.day, report.fixture, report.part, report.answer);
+        }
+    }
+}
+
+#[test]
+fn every_day() {
+    common::check_every_day(check_day);
+}
+
+#[test]
+fn fixtures_belong_to_registered_days() {
+    for entry in fs::read_dir(FIXTURES).unwrap() {
+        let name = entry.unwrap().file_name().into_string().unwrap();
+        let day = name.strip_prefix("day").and_then(|day| day.parse().ok());
+        assert!(day.and_then(get_day).is_some(), "fixtures/{} is not a registered day", name);
+    }
+}
@@ -0,0 +1,465 @@
+//! Property tests for every day's parser. Puzzle data generated here is written
+//! out in the input format and should parse back to the same thing, and no
+//! input at all, however mangled, should make a day panic
+
+use std::collections::HashSet;
+
+use proptest::{collection::vec, prelude::*, sample::{select, Index}};
+
+use advent_of_code::{
+    DAYS,
+    common::{grid::Grid, interval::Interval},
+    q1::Q1, q2::Q2, q3::Q3, q4::Q4, q5::Q5, q6::Q6,
+    q7::{Directory, File, FileSystem, Q7},
+    q8::Q8, q9::Q9, q10::Q10, q11::{MonkeyManager, Q11},
+    solution::Solution,
+};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures");
+
+/**
+ * Parse `input` as every day and solve whatever parses, which must not panic
+ */
+fn solve_every_day(input: &str) {
+    for day in DAYS {
+        if let Ok(parsed) = day.parse(input) {
+            day.part1(&parsed);
+            day.part2(&parsed);
+        }
+    }
+}
+
+/// Pieces of valid and nearly valid lines from across the puzzles
+const FRAGMENTS: &[&str] = &[
+    "", "0", "1", "-1", "4294967296", "18446744073709551615", "A X", "C Z", "B", "vJrwpWtwJgWr", "wM",
+    "2-4,6-8", "5-1,2-3", "    [D]", "[Z] [M] [P]", " 1   2   3", "move 1 from 2 to 1", "move 4294967295 from 1 to 2",
+    "move 1 from 0 to 1", "mjqjpqmgbljsphdztnvjfqwrcgsmlb", "$ cd /", "$ cd ..", "$ cd c", "$ ls", "dir a",
+    "14848514 b.txt", "18446744073709551615 c", "30373", "25512", "R 4", "U 2147483648", "L -1", "noop", "addx 3",
+    "addx -9223372036854775808", "addx 9223372036854775807", "Monkey 0:", "Monkey 1:",
+    "  Starting items: 79, 98", "  Starting items: 18446744073709551615", "  Operation: new = old * 19",
+    "  Operation: new = old * old", "  Operation: new = old + 18446744073709551615", "  Test: divisible by 23",
+    "  Test: divisible by 0", "  Test: divisible by 4294967311", "    If true: throw to monkey 1",
+    "    If false: throw to monkey 0", "    If false: throw to monkey 9",
+];
+
+fn fragment() -> impl Strategy<Value = String> {
+    prop_oneof![
+        select(FRAGMENTS).prop_map(String::from),
+        "[ -~]{0,12}",
+        any::<i64>().prop_map(|n| n.to_string()),
+    ]
+}
+
+#[derive(Clone, Debug)]
+enum Edit {
+    Delete(Index),
+    Insert(Index, String),
+    /// Swap one space separated word of a line for something else
+    Replace(Index, Index, String),
+    Truncate(Index, Index),
+}
+
+fn edit() -> impl Strategy<Value = Edit> {
+    prop_oneof![
+        any::<Index>().prop_map(Edit::Delete),
+        (any::<Index>(), fragment()).prop_map(|(at, text)| Edit::Insert(at, text)),
+        (any::<Index>(), any::<Index>(), fragment()).prop_map(|(at, word, text)| Edit::Replace(at, word, text)),
+        (any::<Index>(), any::<Index>()).prop_map(|(at, length)| Edit::Truncate(at, length)),
+    ]
+}
+
+fn apply(input: &str, edits: &[Edit]) -> String {
+    let mut lines: Vec<String> = input.lines().map(String::from).collect();
+    for edit in edits {
+        match edit {
+            Edit::Insert(at, text) => lines.insert(at.index(lines.len() + 1), text.clone()),
+            _ if lines.is_empty() => {},
+            Edit::Delete(at) => { lines.remove(at.index(lines.len())); },
+            Edit::Replace(at, word, text) => {
+                let at = at.index(lines.len());
+                let line = &mut lines[at];
+                let mut words: Vec<&str> = line.split(' ').collect();
+                let word = word.index(words.len());
+                words[word] = text;
+                *line = words.join(" ");
+            },
+            Edit::Truncate(at, length) => {
+                let at = at.index(lines.len());
+                let line = &mut lines[at];
+                let length = length.index(line.len() + 1);
+                if line.is_char_boundary(length) {
+                    line.truncate(length);
+                }
+            },
+        }
+    }
+    lines.join("\n")
+}
+
+proptest! {
+    #[test]
+    fn no_input_panics(input in any::<String>()) {
+        solve_every_day(&input);
+    }
+
+    #[test]
+    fn no_jumble_of_puzzle_lines_panics(lines in vec(fragment(), 0..24)) {
+        solve_every_day(&lines.join("\n"));
+    }
+
+    #[test]
+    fn no_mangled_example_panics(day in 0..DAYS.len(), edits in vec(edit(), 1..6)) {
+        let day = DAYS[day].day;
+        let example = std::fs::read_to_string(format!("{}/day{}/example.input", FIXTURES, day)).unwrap();
+        solve_every_day(&apply(&example, &edits));
+    }
+}
+
+/// The Calories of each snack each elf carries
+fn elves() -> impl Strategy<Value = Vec<Vec<u64>>> {
+    vec(vec(0..100_000u64, 1..6), 1..20)
+}
+
+proptest! {
+    #[test]
+    fn q1_round_trip(elves in elves()) {
+        let input: String = elves.iter()
+            .map(|elf| elf.iter().map(|snack| format!("{}\n", snack)).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let parsed = Q1::parse(&input).unwrap();
+        let items: Vec<Vec<u64>> = parsed.iter().map(|elf| elf.items.clone()).collect();
+        prop_assert_eq!(&items, &elves);
+
+        let mut totals: Vec<u64> = elves.iter().map(|elf| elf.iter().sum()).collect();
+        totals.sort_unstable_by(|a, b| b.cmp(a));
+        prop_assert_eq!(Q1::part1(&parsed), totals[0]);
+        prop_assert_eq!(Q1::part2(&parsed), totals.iter().take(3).sum::<u64>());
+    }
+
+    #[test]
+    fn q2_round_trip(rounds in vec((0..3u64, 0..3u64), 0..50)) {
+        let input: String = rounds.iter()
+            .map(|&(theirs, column)| format!("{} {}\n", (b'A' + theirs as u8) as char, (b'X' + column as u8) as char))
+            .collect();
+        let parsed = Q2::parse(&input).unwrap();
+
+        // Rock, paper and scissors are 0, 1 and 2, and each beats the one before
+        let score = |theirs: u64, mine: u64| mine + 1 + (mine + 4 - theirs) % 3 * 3;
+        let as_moves: u64 = rounds.iter().map(|&(theirs, column)| score(theirs, column)).sum();
+        let as_outcomes: u64 = rounds.iter().map(|&(theirs, column)| score(theirs, (theirs + column + 2) % 3)).sum();
+        prop_assert_eq!(Q2::part1(&parsed), as_moves);
+        prop_assert_eq!(Q2::part2(&parsed), as_outcomes);
+    }
+
+    #[test]
+    fn q3_round_trip(rucksacks in vec((select(('a'..='z').chain('A'..='Z').collect::<Vec<char>>()), "[a-z]{0,10}", "[A-Z]{0,10}"), 0..20)) {
+        // Only the shared item can be in both compartments
+        let rucksacks: Vec<(char, String)> = rucksacks.into_iter()
+            .map(|(shared, lower, upper)| {
+                let first: String = lower.chars().filter(|&c| c != shared).collect();
+                let second: String = upper.chars().filter(|&c| c != shared).collect();
+                let length = first.len().min(second.len());
+                (shared, format!("{}{}{}{}", &first[..length], shared, &second[..length], shared))
+            })
+            .collect();
+        let input: String = rucksacks.iter().map(|(_, rucksack)| format!("{}\n", rucksack)).collect();
+        let parsed = Q3::parse(&input).unwrap();
+        prop_assert_eq!(&parsed, &rucksacks.iter().map(|(_, rucksack)| rucksack.clone()).collect::<Vec<_>>());
+
+        let priority = |c: char| if c.is_ascii_lowercase() { c as u64 - 96 } else { c as u64 - 38 };
+        prop_assert_eq!(Q3::part1(&parsed), rucksacks.iter().map(|&(shared, _)| priority(shared)).sum::<u64>());
+    }
+
+    #[test]
+    fn q4_round_trip(pairs in vec(((0..100i64, 0..100i64), (0..100i64, 0..100i64)), 0..50)) {
+        let pairs: Vec<(Interval, Interval)> = pairs.into_iter()
+            .map(|((a, b), (c, d))| (Interval::new(a.min(b), a.max(b)).unwrap(), Interval::new(c.min(d), c.max(d)).unwrap()))
+            .collect();
+        let input: String = pairs.iter()
+            .map(|(first, second)| format!("{}-{},{}-{}\n", first.lower(), first.upper(), second.lower(), second.upper()))
+            .collect();
+        let parsed = Q4::parse(&input).unwrap();
+        prop_assert_eq!(&parsed, &pairs);
+
+        let inside = |a: &Interval, b: &Interval| a.lower() <= b.lower() && b.upper() <= a.upper();
+        let contained = pairs.iter().filter(|(a, b)| inside(a, b) || inside(b, a)).count();
+        let overlapping = pairs.iter().filter(|(a, b)| a.lower() <= b.upper() && b.lower() <= a.upper()).count();
+        prop_assert_eq!(Q4::part1(&parsed), contained);
+        prop_assert_eq!(Q4::part2(&parsed), overlapping);
+    }
+}
+
+/**
+ * Draw stacks of crates the way the puzzle does, bottoms lined up above the
+ * stack numbers
+ */
+fn draw_crates(stacks: &[Vec<char>]) -> String {
+    let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+    let mut drawing = String::new();
+    for level in (0..height).rev() {
+        let row: Vec<String> = stacks.iter()
+            .map(|stack| stack.get(level).map_or("   ".to_string(), |c| format!("[{}]", c)))
+            .collect();
+        drawing += &format!("{}\n", row.join(" "));
+    }
+    let numbers: Vec<String> = (1..=stacks.len()).map(|number| format!(" {} ", number)).collect();
+    drawing + &numbers.join(" ") + "\n"
+}
+
+/// How many crates to move, from which stack and to which, counting from 0
+type Move = (usize, usize, usize);
+
+fn crane(mut stacks: Vec<Vec<char>>, moves: &[Move], keep_order: bool) -> String {
+    // Moving crates one at a time back onto the same stack changes nothing
+    for &(count, from, to) in moves.iter().filter(|&&(_, from, to)| from != to) {
+        let count = count.min(stacks[from].len());
+        let at = stacks[from].len() - count;
+        let mut moved = stacks[from].split_off(at);
+        if !keep_order {
+            moved.reverse();
+        }
+        stacks[to].extend(moved);
+    }
+    stacks.iter().filter_map(|stack| stack.last()).collect()
+}
+
+fn crates() -> impl Strategy<Value = (Vec<Vec<char>>, Vec<Move>)> {
+    (1..10usize)
+        .prop_flat_map(|width| (
+            vec(vec(select(('A'..='Z').collect::<Vec<char>>()), 0..6), width),
+            vec((0..8usize, 0..width, 0..width), 1..20),
+        ))
+        // Without any crates there's nothing to show how many stacks there are
+        .prop_filter("no crates", |(stacks, _)| stacks.iter().any(|stack| !stack.is_empty()))
+}
+
+proptest! {
+    #[test]
+    fn q5_round_trip((stacks, moves) in crates()) {
+        let instructions: Vec<String> = moves.iter()
+            .map(|(count, from, to)| format!("move {} from {} to {}", count, from + 1, to + 1))
+            .collect();
+        let input = format!("{}\n{}\n", draw_crates(&stacks), instructions.join("\n"));
+        let parsed = Q5::parse(&input).unwrap();
+        prop_assert_eq!(Q5::part1(&parsed), crane(stacks.clone(), &moves, false));
+        prop_assert_eq!(Q5::part2(&parsed), crane(stacks, &moves, true));
+    }
+
+    #[test]
+    fn q6_round_trip(stream in "[a-p]{0,100}") {
+        let parsed = Q6::parse(&format!("{}\n", stream)).unwrap();
+        prop_assert_eq!(&parsed, &stream);
+
+        let chars: Vec<char> = stream.chars().collect();
+        let marker = |length: usize| (length..=chars.len())
+            .find(|&end| chars[end - length..end].iter().collect::<HashSet<_>>().len() == length)
+            .unwrap_or(0);
+        prop_assert_eq!(Q6::part1(&parsed), marker(4));
+        prop_assert_eq!(Q6::part2(&parsed), marker(14));
+    }
+}
+
+/// A directory as generated, before it's written out as a terminal session
+#[derive(Clone, Debug)]
+struct Tree {
+    files: Vec<u64>,
+    directories: Vec<Tree>,
+}
+
+impl Tree {
+    fn size(&self) -> u64 {
+        self.files.iter().sum::<u64>() + self.directories.iter().map(Tree::size).sum::<u64>()
+    }
+
+    fn sizes(&self, sizes: &mut Vec<u64>) {
+        sizes.push(self.size());
+        for directory in &self.directories {
+            directory.sizes(sizes);
+        }
+    }
+
+    fn session(&self, lines: &mut Vec<String>) {
+        lines.push("$ ls".to_string());
+        // Files and directories are listed interleaved
+        for index in 0..self.files.len().max(self.directories.len()) {
+            if let Some(size) = self.files.get(index) {
+                lines.push(format!("{} f{}.txt", size, index));
+            }
+            if index < self.directories.len() {
+                lines.push(format!("dir d{}", index));
+            }
+        }
+        for (index, directory) in self.directories.iter().enumerate() {
+            lines.push(format!("$ cd d{}", index));
+            directory.session(lines);
+            lines.push("$ cd ..".to_string());
+        }
+    }
+
+    fn directory(&self, name: Vec<u8>) -> Directory {
+        let mut directory = Directory::with_name(name);
+        directory.files = self.files.iter().map(|&size| File { size }).collect();
+        directory.child_directories = self.directories.iter()
+            .enumerate()
+            .map(|(index, child)| child.directory(format!("d{}", index).into_bytes()))
+            .collect();
+        directory
+    }
+}
+
+fn tree() -> impl Strategy<Value = Tree> {
+    let files = || vec(1..200_000u64, 0..4);
+    files()
+        .prop_map(|files| Tree { files, directories: Vec::new() })
+        .prop_recursive(4, 32, 4, move |inner| {
+            (files(), vec(inner, 0..4)).prop_map(|(files, directories)| Tree { files, directories })
+        })
+}
+
+proptest! {
+    #[test]
+    fn q7_round_trip(tree in tree()) {
+        let mut lines = vec!["$ cd /".to_string()];
+        tree.session(&mut lines);
+        let parsed = Q7::parse(&lines.join("\n")).unwrap();
+        prop_assert_eq!(&parsed, &FileSystem { root_directory: tree.directory(b"/".to_vec()) });
+
+        let mut sizes = Vec::new();
+        tree.sizes(&mut sizes);
+        prop_assert_eq!(Q7::part1(&parsed), sizes.iter().filter(|&&size| size < 100_000).sum::<u64>());
+    }
+
+    #[test]
+    fn q8_round_trip(rows in (1..12usize).prop_flat_map(|width| vec(vec(0..10u32, width), 1..12))) {
+        let input: String = rows.iter()
+            .map(|row| row.iter().map(|height| height.to_string()).collect::<String>() + "\n")
+            .collect();
+        let parsed = Q8::parse(&input).unwrap();
+        prop_assert_eq!(&parsed, &Grid::from_rows(rows.clone()).unwrap());
+
+        let (height, width) = (rows.len(), rows[0].len());
+        let visible = (0..height)
+            .flat_map(|row| (0..width).map(move |column| (row, column)))
+            .filter(|&(row, column)| {
+                let tree = rows[row][column];
+                (0..column).all(|other| rows[row][other] < tree)
+                    || (column + 1..width).all(|other| rows[row][other] < tree)
+                    || (0..row).all(|other| rows[other][column] < tree)
+                    || (row + 1..height).all(|other| rows[other][column] < tree)
+            })
+            .count();
+        prop_assert_eq!(Q8::part1(&parsed) as usize, visible);
+    }
+}
+
+/**
+ * The number of places the tail of a rope of `knots` knots visits
+ */
+fn tail_positions(moves: &[(char, i32)], knots: usize) -> usize {
+    let mut rope = vec![(0i32, 0i32); knots];
+    let mut visited = HashSet::from([(0, 0)]);
+    for &(direction, steps) in moves {
+        let (dx, dy) = match direction {
+            'R' => (1, 0),
+            'L' => (-1, 0),
+            'U' => (0, 1),
+            _ => (0, -1),
+        };
+        for _ in 0..steps {
+            rope[0] = (rope[0].0 + dx, rope[0].1 + dy);
+            for knot in 1..knots {
+                let (x, y) = (rope[knot - 1].0 - rope[knot].0, rope[knot - 1].1 - rope[knot].1);
+                if x.abs() > 1 || y.abs() > 1 {
+                    rope[knot] = (rope[knot].0 + x.signum(), rope[knot].1 + y.signum());
+                }
+            }
+            visited.insert(rope[knots - 1]);
+        }
+    }
+    visited.len()
+}
+
+/// Instructions for the CPU, which adds and skips as the puzzle does
+fn instruction() -> impl Strategy<Value = String> {
+    prop_oneof![Just("noop".to_string()), (-40..40i64).prop_map(|value| format!("addx {}", value))]
+}
+
+proptest! {
+    #[test]
+    fn q9_round_trip(moves in vec((select(vec!['R', 'L', 'U', 'D']), 0..12i32), 0..40)) {
+        let lines: Vec<String> = moves.iter().map(|(direction, steps)| format!("{} {}", direction, steps)).collect();
+        let parsed = Q9::parse(&lines.join("\n")).unwrap();
+        prop_assert_eq!(parsed.iter().map(ToString::to_string).collect::<Vec<_>>(), lines);
+        prop_assert_eq!(Q9::part1(&parsed), tail_positions(&moves, 2));
+        prop_assert_eq!(Q9::part2(&parsed), tail_positions(&moves, 10));
+    }
+
+    #[test]
+    fn q10_round_trip(program in vec(instruction(), 0..300)) {
+        let parsed = Q10::parse(&program.join("\n")).unwrap();
+        prop_assert_eq!(parsed.iter().map(ToString::to_string).collect::<Vec<_>>(), program);
+
+        let screen = Q10::part2(&parsed);
+        prop_assert_eq!(screen.lines().count(), 6);
+        prop_assert!(screen.lines().all(|row| row.chars().count() == 40));
+    }
+}
+
+/// A monkey as generated: items, operation, divisor and who it throws to
+type Monkey = (Vec<u64>, String, u64, usize, usize);
+
+fn monkeys() -> impl Strategy<Value = Vec<Monkey>> {
+    let operation = prop_oneof![
+        (1..20u64).prop_map(|n| format!("+ {}", n)),
+        (1..20u64).prop_map(|n| format!("* {}", n)),
+        Just("* old".to_string()),
+    ];
+    (2..6usize).prop_flat_map(move |count| vec(
+        (vec(0..100u64, 0..5), operation.clone(), select(vec![2u64, 3, 5, 7, 11, 13, 17, 19, 23]), 1..count, 1..count),
+        count,
+    ))
+}
+
+fn describe_monkeys(monkeys: &[Monkey]) -> String {
+    let blocks: Vec<String> = monkeys.iter()
+        .enumerate()
+        .map(|(id, (items, operation, divisor, on_true, on_false))| {
+            let items: Vec<String> = items.iter().map(u64::to_string).collect();
+            // Offsetting by at least one means never throwing to itself
+            let throw = |offset: usize| (id + offset) % monkeys.len();
+            format!(
+                "Monkey {}:\n  Starting items: {}\n  Operation: new = old {}\n  Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey {}\n",
+                id, items.join(", "), operation, divisor, throw(*on_true), throw(*on_false),
+            )
+        })
+        .collect();
+    blocks.join("\n")
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn q11_round_trip(monkeys in monkeys(), rounds in 0..60u64) {
+        let parsed = Q11::parse(&describe_monkeys(&monkeys)).unwrap();
+        let described: Vec<String> = monkeys.iter()
+            .enumerate()
+            .map(|(id, (items, ..))| {
+                let items: Vec<String> = items.iter().map(u64::to_string).collect();
+                format!("Monkey {}:{}{}", id, if items.is_empty() { "" } else { " " }, items.join(", "))
+            })
+            .collect();
+        let shown = parsed.to_string();
+        prop_assert_eq!(shown.lines().collect::<Vec<_>>(), described);
+
+        // Extrapolating from each item's cycle agrees with playing every round
+        let mut played: MonkeyManager = parsed.clone();
+        for _ in 0..rounds {
+            played.play_round();
+        }
+        prop_assert_eq!(parsed.inspections_after(rounds), played.inspections());
+        Q11::part1(&parsed);
+        Q11::part2(&parsed);
+    }
+}